@@ -0,0 +1,19 @@
+//! Feeds arbitrary input to every parsing entry point, which should only ever return an error on
+//! malformed input and never panic.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(story) = fimfiction_api::from_str(input) {
+        // What was parsed should be serializable back.
+        fimfiction_api::to_string(story).expect("a parsed story should be serializable");
+    }
+    let _ = fimfiction_api::parse_response(input);
+    let _ = fimfiction_api::from_str_nested(input);
+    let _ = fimfiction_api::cow::from_str_borrowed(input);
+});
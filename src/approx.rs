@@ -0,0 +1,67 @@
+//! Approximate comparisons of floating point metrics, like [`Story::like_ratio()`].
+//!
+//! Metrics computed with floating point arithmetic can be off by rounding errors, which makes
+//! exact comparisons on them brittle. [`approx_eq()`] and [`assert_approx_eq!`] compare them
+//! within a tolerance instead:
+//! ```
+//! use fimfiction_api::assert_approx_eq;
+//!
+//! assert_approx_eq!(0.1 + 0.2, 0.3);
+//! assert_approx_eq!(0.99, 0.992, 0.01);
+//! ```
+//!
+//! [`Story::like_ratio()`]: crate::Story::like_ratio
+//! [`assert_approx_eq!`]: crate::assert_approx_eq
+
+/// Default tolerance of [`assert_approx_eq!`](crate::assert_approx_eq).
+pub const EPSILON: f64 = 1e-9;
+
+/// Whether `left` and `right` differ by at most `epsilon`.
+///
+/// Equal infinities are approximately equal, while `NaN` isn't approximately equal to anything.
+pub fn approx_eq(left: f64, right: f64, epsilon: f64) -> bool {
+    left == right || (left - right).abs() <= epsilon
+}
+
+/// Asserts that two `f64` expressions are [approximately equal](crate::approx::approx_eq), within
+/// [`EPSILON`](crate::approx::EPSILON) or the given tolerance.
+///
+/// On panic, prints the values of both expressions and the tolerance.
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_approx_eq!($left, $right, $crate::approx::EPSILON)
+    };
+    ($left:expr, $right:expr, $epsilon:expr $(,)?) => {
+        match (&$left, &$right, &$epsilon) {
+            (left, right, epsilon) => {
+                if !$crate::approx::approx_eq(*left, *right, *epsilon) {
+                    panic!(
+                        "assertion `left ≈ right` failed (epsilon: {})\n  left: {}\n right: {}",
+                        epsilon, left, right
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn approx_eq_within_epsilon() {
+        assert!(approx_eq(0.1 + 0.2, 0.3, EPSILON));
+        assert!(approx_eq(1.0, 1.5, 0.5));
+        assert!(!approx_eq(1.0, 1.5, 0.25));
+        assert!(approx_eq(f64::INFINITY, f64::INFINITY, EPSILON));
+        assert!(!approx_eq(f64::NAN, f64::NAN, f64::INFINITY));
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion `left ≈ right` failed")]
+    fn assert_approx_eq_panics() {
+        crate::assert_approx_eq!(1.0, 1.1);
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::Id;
+
+/// Container struct of the author response given by the Fimfiction story API.
+///
+/// Some trimmed responses give the author as a bare ID instead of an object, in which case
+/// [`name`](Author::name) is left empty.
+///
+/// The [`Default`] author, with an ID of `0` and an empty name, stands for an unknown author.
+#[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Author {
+    /// Author's ID.
+    pub id: Id,
+    /// Username of the author.
+    pub name: String,
+}
+
+impl Author {
+    /// Whether this is the unknown [`Default`] author.
+    pub fn is_unknown(&self) -> bool {
+        self.id == 0 && self.name.is_empty()
+    }
+
+    /// Fimfiction URL to the listing of the stories of the author.
+    ///
+    /// Built from the [`id`](Author::id) alone, so it works for authors given as a bare ID.
+    pub fn stories_url(&self) -> String {
+        format!("https://www.fimfiction.net/user/{}/stories", self.id)
+    }
+}
+
+/// The forms an author takes in API responses, generic over the type of its name.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum AuthorRepr<N> {
+    Full { id: Id, name: N },
+    Id(Id),
+}
+
+impl<N: Default> AuthorRepr<N> {
+    /// The ID and name of the author, with an empty name for a bare ID.
+    pub(crate) fn into_parts(self) -> (Id, N) {
+        match self {
+            AuthorRepr::Full { id, name } => (id, name),
+            AuthorRepr::Id(id) => (id, N::default()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Author {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (id, name) = AuthorRepr::<String>::deserialize(deserializer)?.into_parts();
+        Ok(Author { id, name })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn deserialize_object() {
+        let value = json!({ "id": 253168, "name": "Rambling Writer" });
+        let author: Author =
+            serde_json::from_value(value).expect("Author should be deserializable");
+        assert_eq!(author.id, 253168);
+        assert_eq!(author.name, "Rambling Writer");
+    }
+
+    #[test]
+    fn deserialize_bare_id() {
+        let value = json!(253168);
+        let author: Author =
+            serde_json::from_value(value).expect("Author should be deserializable");
+        assert_eq!(author.id, 253168);
+        assert_eq!(author.name, "");
+    }
+
+    #[test]
+    fn stories_url() {
+        let author: Author = serde_json::from_value(json!(253168)).unwrap();
+        assert_eq!(
+            author.stories_url(),
+            "https://www.fimfiction.net/user/253168/stories"
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        let value = json!("Rambling Writer");
+        serde_json::from_value::<Author>(value).expect_err("a string is not a valid Author");
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+use crate::Story;
+
+/// The word count brackets of the Fimfiction story search, given by
+/// [`Story::word_count_bracket()`].
+///
+/// Implements [`Display`](fmt::Display) with the label of each bracket on the site:
+/// ```
+/// # use fimfiction_api::WordBracket;
+/// assert_eq!(WordBracket::UpTo1000.to_string(), "Under 1,000");
+/// assert_eq!(WordBracket::UpTo5000.to_string(), "1,000–5,000");
+/// assert_eq!(WordBracket::Over100000.to_string(), "Over 100,000");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WordBracket {
+    /// Less than 1,000 words.
+    UpTo1000,
+    /// From 1,000 to less than 5,000 words.
+    UpTo5000,
+    /// From 5,000 to less than 15,000 words.
+    UpTo15000,
+    /// From 15,000 to less than 50,000 words.
+    UpTo50000,
+    /// From 50,000 to less than 100,000 words.
+    UpTo100000,
+    /// 100,000 words or more.
+    Over100000,
+}
+
+impl WordBracket {
+    /// The bracket a story with `words` falls into.
+    pub fn from_words(words: u64) -> WordBracket {
+        match words {
+            0..=999 => WordBracket::UpTo1000,
+            1000..=4999 => WordBracket::UpTo5000,
+            5000..=14999 => WordBracket::UpTo15000,
+            15000..=49999 => WordBracket::UpTo50000,
+            50000..=99999 => WordBracket::UpTo100000,
+            _ => WordBracket::Over100000,
+        }
+    }
+
+    /// The inclusive lower bound and exclusive upper bound of the bracket, `None` for the last one.
+    pub fn range(&self) -> (u64, Option<u64>) {
+        match self {
+            WordBracket::UpTo1000 => (0, Some(1000)),
+            WordBracket::UpTo5000 => (1000, Some(5000)),
+            WordBracket::UpTo15000 => (5000, Some(15000)),
+            WordBracket::UpTo50000 => (15000, Some(50000)),
+            WordBracket::UpTo100000 => (50000, Some(100000)),
+            WordBracket::Over100000 => (100000, None),
+        }
+    }
+
+    /// The `words` filter of the search for the bracket, like `words:>=1000 words:<5000`.
+    ///
+    /// ```
+    /// # use fimfiction_api::WordBracket;
+    /// assert_eq!(WordBracket::UpTo1000.query_param(), "words:<1000");
+    /// assert_eq!(WordBracket::UpTo5000.query_param(), "words:>=1000 words:<5000");
+    /// assert_eq!(WordBracket::Over100000.query_param(), "words:>=100000");
+    /// ```
+    pub fn query_param(&self) -> String {
+        match self.range() {
+            (0, Some(max)) => format!("words:<{max}"),
+            (min, Some(max)) => format!("words:>={min} words:<{max}"),
+            (min, None) => format!("words:>={min}"),
+        }
+    }
+}
+
+impl fmt::Display for WordBracket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WordBracket::UpTo1000 => write!(f, "Under 1,000"),
+            WordBracket::UpTo5000 => write!(f, "1,000–5,000"),
+            WordBracket::UpTo15000 => write!(f, "5,000–15,000"),
+            WordBracket::UpTo50000 => write!(f, "15,000–50,000"),
+            WordBracket::UpTo100000 => write!(f, "50,000–100,000"),
+            WordBracket::Over100000 => write!(f, "Over 100,000"),
+        }
+    }
+}
+
+impl Story {
+    /// The word count bracket of the Fimfiction search the story falls into.
+    pub fn word_count_bracket(&self) -> WordBracket {
+        WordBracket::from_words(self.words)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bracket_bounds() {
+        for (words, bracket) in [
+            (0, WordBracket::UpTo1000),
+            (999, WordBracket::UpTo1000),
+            (1000, WordBracket::UpTo5000),
+            (4999, WordBracket::UpTo5000),
+            (5000, WordBracket::UpTo15000),
+            (15000, WordBracket::UpTo50000),
+            (99999, WordBracket::UpTo100000),
+            (100000, WordBracket::Over100000),
+            (275949, WordBracket::Over100000),
+        ] {
+            assert_eq!(WordBracket::from_words(words), bracket, "{words}");
+
+            let (min, max) = bracket.range();
+            assert!(min <= words && max.is_none_or(|max| words < max), "{words}");
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Story;
+
+/// A [`Story`] along with the time it was fetched, to tell when a cached copy should be fetched
+/// again.
+///
+/// Unlike [`date_modified`](Story::date_modified), which is when the author last updated the
+/// story, [`fetched_at`](CachedStory::fetched_at) is when this copy was taken from the API.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CachedStory {
+    /// The fetched story.
+    pub story: Story,
+    /// When the story was fetched, as a Unix timestamp in seconds.
+    pub fetched_at: i64,
+}
+
+/// The current time as a Unix timestamp in seconds.
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs() as i64)
+}
+
+impl CachedStory {
+    /// Wraps a story that was just fetched, taking the current time as
+    /// [`fetched_at`](CachedStory::fetched_at).
+    pub fn new(story: Story) -> Self {
+        CachedStory {
+            story,
+            fetched_at: now(),
+        }
+    }
+
+    /// Whether more than `ttl` passed since the story was fetched.
+    ///
+    /// A [`fetched_at`](CachedStory::fetched_at) in the future, like from clock skew, is never
+    /// expired.
+    pub fn is_expired(&self, ttl: Duration) -> bool {
+        self.is_expired_at(now(), ttl)
+    }
+
+    fn is_expired_at(&self, now: i64, ttl: Duration) -> bool {
+        let age = now.saturating_sub(self.fetched_at);
+        age > 0 && age as u64 > ttl.as_secs()
+    }
+}
+
+impl From<CachedStory> for Story {
+    fn from(cached: CachedStory) -> Self {
+        cached.story
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn is_expired() {
+        let story = Story {
+            id: 1,
+            chapter_count: 1,
+            ..Story::default()
+        };
+
+        let cached = CachedStory::new(story);
+        assert!(!cached.is_expired(Duration::from_secs(60)));
+
+        let ttl = Duration::from_secs(3600);
+        let fetched_at = cached.fetched_at;
+        assert!(!cached.is_expired_at(fetched_at + 3600, ttl));
+        assert!(cached.is_expired_at(fetched_at + 3601, ttl));
+        assert!(!cached.is_expired_at(fetched_at - 10, ttl));
+    }
+}
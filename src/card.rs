@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Author, Id, Story, StoryStatus};
+
+/// Lightweight summary of a story for list views, given by [`Story::to_card()`].
+///
+/// Leaves out the descriptions and chapters, which make most of the size of a story.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StoryCard {
+    /// [`id`](Story::id) of the story.
+    pub id: Id,
+    /// [`title`](Story::title) of the story.
+    pub title: String,
+    /// [`author`](Story::author) of the story.
+    pub author: Author,
+    /// Cover thumbnail of the story, its [`image`](Story::image).
+    pub image: Option<String>,
+    /// [`words`](Story::words) of the story.
+    pub words: u64,
+    /// [`status`](Story::status) of the story.
+    pub status: StoryStatus,
+    /// [`like_ratio`](Story::like_ratio) of the story.
+    pub like_ratio: Option<f64>,
+}
+
+impl Story {
+    /// The [`StoryCard`] of the story.
+    pub fn to_card(&self) -> StoryCard {
+        StoryCard {
+            id: self.id,
+            title: self.title.clone(),
+            author: self.author.clone(),
+            image: self.image.clone(),
+            words: self.words,
+            status: self.status,
+            like_ratio: self.like_ratio(),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Chapter, Id};
+
+/// The chapters of a [`Story`](crate::Story) in reading order, with helpers to navigate
+/// between them.
+///
+/// Dereferences into a slice of [`Chapter`]s, so it can be used as one.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct ChapterList(Vec<Chapter>);
+
+impl ChapterList {
+    /// Position of the chapter with the given `id`.
+    fn position(&self, id: Id) -> Option<usize> {
+        self.0.iter().position(|chapter| chapter.id == id)
+    }
+
+    /// The chapter that comes after the one with the given `id`.
+    ///
+    /// Returns `None` if there isn't a chapter with that ID or it is the last one.
+    pub fn next_after(&self, id: Id) -> Option<&Chapter> {
+        self.0.get(self.position(id)? + 1)
+    }
+
+    /// The chapter that comes before the one with the given `id`.
+    ///
+    /// Returns `None` if there isn't a chapter with that ID or it is the first one.
+    pub fn prev_before(&self, id: Id) -> Option<&Chapter> {
+        self.0.get(self.position(id)?.checked_sub(1)?)
+    }
+
+    /// The chapter with the given number, starting from `1` like in Fimfiction chapter URLs.
+    pub fn by_number(&self, number: u32) -> Option<&Chapter> {
+        self.0.get(usize::try_from(number).ok()?.checked_sub(1)?)
+    }
+
+    /// Appends a chapter at the end.
+    pub fn push(&mut self, chapter: Chapter) {
+        self.0.push(chapter);
+    }
+
+    /// Removes the last chapter and returns it, if any.
+    pub fn pop(&mut self) -> Option<Chapter> {
+        self.0.pop()
+    }
+
+    /// Keeps only the first `len` chapters.
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    /// Keeps only the chapters for which `keep` returns `true`, in order.
+    pub fn retain(&mut self, keep: impl FnMut(&Chapter) -> bool) {
+        self.0.retain(keep);
+    }
+
+    /// Removes all chapters.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Converts the list into a [`Vec`] of chapters.
+    pub fn into_vec(self) -> Vec<Chapter> {
+        self.0
+    }
+}
+
+impl Deref for ChapterList {
+    type Target = [Chapter];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ChapterList {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Vec<Chapter>> for ChapterList {
+    fn from(chapters: Vec<Chapter>) -> Self {
+        ChapterList(chapters)
+    }
+}
+
+impl From<ChapterList> for Vec<Chapter> {
+    fn from(chapters: ChapterList) -> Self {
+        chapters.0
+    }
+}
+
+impl FromIterator<Chapter> for ChapterList {
+    fn from_iter<I: IntoIterator<Item = Chapter>>(iter: I) -> Self {
+        ChapterList(iter.into_iter().collect())
+    }
+}
+
+impl Extend<Chapter> for ChapterList {
+    fn extend<I: IntoIterator<Item = Chapter>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl IntoIterator for ChapterList {
+    type Item = Chapter;
+    type IntoIter = std::vec::IntoIter<Chapter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ChapterList {
+    type Item = &'a Chapter;
+    type IntoIter = std::slice::Iter<'a, Chapter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut ChapterList {
+    type Item = &'a mut Chapter;
+    type IntoIter = std::slice::IterMut<'a, Chapter>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn chapter(id: Id) -> Chapter {
+        Chapter {
+            id,
+            title: format!("Chapter {id}"),
+            words: 1000,
+            views: 0,
+            link: String::new(),
+            date_modified: crate::Date::default(),
+        }
+    }
+
+    #[test]
+    fn navigation() {
+        let chapters: ChapterList = [10, 20, 30].into_iter().map(chapter).collect();
+
+        assert_eq!(chapters.next_after(10).map(|chapter| chapter.id), Some(20));
+        assert_eq!(chapters.next_after(30).map(|chapter| chapter.id), None);
+        assert_eq!(chapters.next_after(40).map(|chapter| chapter.id), None);
+
+        assert_eq!(chapters.prev_before(30).map(|chapter| chapter.id), Some(20));
+        assert_eq!(chapters.prev_before(10).map(|chapter| chapter.id), None);
+        assert_eq!(chapters.prev_before(40).map(|chapter| chapter.id), None);
+
+        assert_eq!(chapters.by_number(1).map(|chapter| chapter.id), Some(10));
+        assert_eq!(chapters.by_number(3).map(|chapter| chapter.id), Some(30));
+        assert_eq!(chapters.by_number(0).map(|chapter| chapter.id), None);
+        assert_eq!(chapters.by_number(4).map(|chapter| chapter.id), None);
+    }
+}
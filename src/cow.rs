@@ -0,0 +1,485 @@
+//! Variants of [`Story`] and the types it contains with [`Cow<str>`] fields, which borrow their
+//! text from the response unless it has escape sequences that need to be unescaped.
+//!
+//! ```
+//! # use std::borrow::Cow;
+//! # use fimfiction_api::StoryError;
+//! # let response = &fimfiction_api::to_string(fimfiction_api::Story {
+//! #     id: 1,
+//! #     title: "A story".to_string(),
+//! #     chapter_count: 1,
+//! #     ..Default::default()
+//! # })?;
+//! let story = fimfiction_api::cow::from_str_borrowed(response)?;
+//! assert!(matches!(story.title, Cow::Borrowed("A story")));
+//! # Ok::<(), StoryError>(())
+//! ```
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::author::AuthorRepr;
+use crate::rating::{self, NestedRating, RatingText};
+use crate::{
+    date, tag, vote, Author, Chapter, Date, GroupLink, Id, PublicationState, Story, StoryError,
+    StoryRating, StoryStatus, Tag,
+};
+
+/// [`Author`] with borrowed text.
+///
+/// Like an [`Author`], it can be deserialized from a bare ID, leaving the name empty.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AuthorCow<'a> {
+    /// Author's ID.
+    pub id: Id,
+    /// Username of the author.
+    pub name: Cow<'a, str>,
+}
+
+/// A [`Cow<str>`] borrowing from the input when it can, to be used as the name of an
+/// [`AuthorRepr`] or inside an [`Option`].
+#[derive(Default, Deserialize)]
+struct CowStr<'a>(#[serde(borrow)] Cow<'a, str>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for AuthorCow<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (id, CowStr(name)) = AuthorRepr::<CowStr<'a>>::deserialize(deserializer)?.into_parts();
+        Ok(AuthorCow { id, name })
+    }
+}
+
+impl AuthorCow<'_> {
+    /// Converts into an owned [`Author`].
+    pub fn into_owned(self) -> Author {
+        Author {
+            id: self.id,
+            name: self.name.into_owned(),
+        }
+    }
+}
+
+impl PartialEq<Author> for AuthorCow<'_> {
+    fn eq(&self, other: &Author) -> bool {
+        self.id == other.id && self.name == other.name
+    }
+}
+
+impl PartialEq<AuthorCow<'_>> for Author {
+    fn eq(&self, other: &AuthorCow<'_>) -> bool {
+        other == self
+    }
+}
+
+/// [`Chapter`] with borrowed text.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChapterCow<'a> {
+    /// Chapter's ID.
+    pub id: Id,
+    /// Title of the chapter.
+    #[serde(borrow)]
+    pub title: Cow<'a, str>,
+    /// The amount of words the chapter has.
+    pub words: u64,
+    /// The amount of views the chapter has, `0` if hidden.
+    #[serde(with = "vote::or_zero")]
+    pub views: u32,
+    /// Fimfiction URL to the story's chapter.
+    #[serde(borrow)]
+    pub link: Cow<'a, str>,
+    /// Last chapter update, same as [`Chapter::date_modified`].
+    #[serde(with = "date", alias = "dateModified")]
+    pub date_modified: Date,
+}
+
+impl ChapterCow<'_> {
+    /// Converts into an owned [`Chapter`].
+    pub fn into_owned(self) -> Chapter {
+        Chapter {
+            id: self.id,
+            title: self.title.into_owned(),
+            words: self.words,
+            views: self.views,
+            link: self.link.into_owned(),
+            date_modified: self.date_modified,
+        }
+    }
+}
+
+impl PartialEq<Chapter> for ChapterCow<'_> {
+    fn eq(&self, other: &Chapter) -> bool {
+        self.id == other.id
+            && self.title == other.title
+            && self.words == other.words
+            && self.views == other.views
+            && self.link == other.link
+            && self.date_modified == other.date_modified
+    }
+}
+
+impl PartialEq<ChapterCow<'_>> for Chapter {
+    fn eq(&self, other: &ChapterCow<'_>) -> bool {
+        other == self
+    }
+}
+
+/// [`Story`] with borrowed text.
+///
+/// Can be compared with a [`Story`], being equal when both have the same data.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(
+    try_from = "StoryCowRepr<'a>",
+    into = "StoryCowRepr<'a>",
+    bound(deserialize = "'de: 'a")
+)]
+pub struct StoryCow<'a> {
+    /// Unique story ID.
+    pub id: Id,
+    /// Title of the story.
+    pub title: Cow<'a, str>,
+    /// Fimfiction URL to the story.
+    pub url: Cow<'a, str>,
+    /// Summary of the story, see [`Story::short_description`].
+    pub short_description: Cow<'a, str>,
+    /// Complete story description, see [`Story::description`].
+    pub description: Cow<'a, str>,
+    /// Last story update, same as [`Story::date_modified`].
+    pub date_modified: Date,
+    /// Story cover image in thumbnail size if any.
+    pub image: Option<Cow<'a, str>>,
+    /// Story cover image in full size if any.
+    pub full_image: Option<Cow<'a, str>>,
+    /// The views the story has, `0` if hidden.
+    pub views: u32,
+    /// The total views the story has, `0` if hidden.
+    pub total_views: u32,
+    /// The amount of words the story has.
+    pub words: u64,
+    /// The amount of chapters the story has.
+    pub chapter_count: u64,
+    /// The amount of comments the story has, `0` if missing.
+    pub comments: u32,
+    /// Author of the story, the [`Default`] one if missing.
+    pub author: AuthorCow<'a>,
+    /// Story completion status.
+    pub status: StoryStatus,
+    /// Story publication state, if given by the API.
+    pub publication_state: Option<PublicationState>,
+    /// Language the story is written in, if given by the API.
+    pub language: Option<Cow<'a, str>>,
+    /// Rating given to the story, see [`Story::content_rating`].
+    pub content_rating: StoryRating,
+    /// The amount of likes the story has, if not disabled.
+    pub likes: Option<u32>,
+    /// The amount of dislikes the story has, if not disabled.
+    pub dislikes: Option<u32>,
+    /// Tags of the story, if given by the API.
+    ///
+    /// Can also be deserialized from a comma-separated string of tag names, see [`Story::tags`].
+    pub tags: Vec<Tag>,
+    /// Groups the story was posted to, if given by the API.
+    pub groups: Vec<GroupLink>,
+    /// Chapters of the story.
+    pub chapters: Vec<ChapterCow<'a>>,
+}
+
+impl StoryCow<'_> {
+    /// Converts into an owned [`Story`].
+    pub fn into_owned(self) -> Story {
+        Story {
+            id: self.id,
+            title: self.title.into_owned(),
+            url: self.url.into_owned(),
+            short_description: self.short_description.into_owned(),
+            description: self.description.into_owned(),
+            date_modified: self.date_modified,
+            image: self.image.map(Cow::into_owned),
+            full_image: self.full_image.map(Cow::into_owned),
+            views: self.views,
+            total_views: self.total_views,
+            words: self.words,
+            chapter_count: self.chapter_count,
+            comments: self.comments,
+            author: self.author.into_owned(),
+            status: self.status,
+            publication_state: self.publication_state,
+            language: self.language.map(Cow::into_owned),
+            content_rating: self.content_rating,
+            likes: self.likes,
+            dislikes: self.dislikes,
+            tags: self.tags,
+            groups: self.groups,
+            chapters: self
+                .chapters
+                .into_iter()
+                .map(ChapterCow::into_owned)
+                .collect(),
+        }
+    }
+}
+
+impl PartialEq<Story> for StoryCow<'_> {
+    fn eq(&self, other: &Story) -> bool {
+        // Destructured without `..` so that a field added to `Story` can't be missed here.
+        let Story {
+            id,
+            title,
+            url,
+            short_description,
+            description,
+            date_modified,
+            image,
+            full_image,
+            views,
+            total_views,
+            words,
+            chapter_count,
+            comments,
+            author,
+            status,
+            publication_state,
+            language,
+            content_rating,
+            likes,
+            dislikes,
+            tags,
+            groups,
+            chapters,
+        } = other;
+
+        self.id == *id
+            && self.title == *title
+            && self.url == *url
+            && self.short_description == *short_description
+            && self.description == *description
+            && self.date_modified == *date_modified
+            && self.image.as_deref() == image.as_deref()
+            && self.full_image.as_deref() == full_image.as_deref()
+            && self.views == *views
+            && self.total_views == *total_views
+            && self.words == *words
+            && self.chapter_count == *chapter_count
+            && self.comments == *comments
+            && self.author == *author
+            && self.status == *status
+            && self.publication_state == *publication_state
+            && self.language.as_deref() == language.as_deref()
+            && self.content_rating == *content_rating
+            && self.likes == *likes
+            && self.dislikes == *dislikes
+            && self.tags == *tags
+            && self.groups == *groups
+            && self.chapters.len() == chapters.len()
+            && self.chapters.iter().zip(chapters).all(|(a, b)| a == b)
+    }
+}
+
+impl PartialEq<StoryCow<'_>> for Story {
+    fn eq(&self, other: &StoryCow<'_>) -> bool {
+        other == self
+    }
+}
+
+/// The form a [`StoryCow`] takes in API responses, same as the one of a [`Story`].
+#[derive(Deserialize, Serialize)]
+struct StoryCowRepr<'a> {
+    id: Id,
+    #[serde(borrow)]
+    title: Cow<'a, str>,
+    #[serde(borrow)]
+    url: Cow<'a, str>,
+    #[serde(borrow, alias = "shortDescription")]
+    short_description: Cow<'a, str>,
+    #[serde(borrow)]
+    description: Cow<'a, str>,
+    #[serde(with = "date", alias = "dateModified")]
+    date_modified: Date,
+    #[serde(borrow, default, deserialize_with = "borrow_option")]
+    image: Option<Cow<'a, str>>,
+    #[serde(
+        borrow,
+        default,
+        alias = "fullImage",
+        deserialize_with = "borrow_option"
+    )]
+    full_image: Option<Cow<'a, str>>,
+    #[serde(with = "vote::or_zero", alias = "num_views")]
+    views: u32,
+    #[serde(
+        with = "vote::or_zero",
+        alias = "total_num_views",
+        alias = "totalViews"
+    )]
+    total_views: u32,
+    words: u64,
+    #[serde(
+        alias = "num_chapters",
+        alias = "number_of_chapters",
+        alias = "chapterCount"
+    )]
+    chapter_count: u64,
+    #[serde(default, alias = "num_comments")]
+    comments: u32,
+    #[serde(borrow, default)]
+    author: AuthorCow<'a>,
+    status: StoryStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    publication_state: Option<PublicationState>,
+    #[serde(
+        borrow,
+        default,
+        deserialize_with = "borrow_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    language: Option<Cow<'a, str>>,
+    #[serde(alias = "contentRatingText", skip_serializing_if = "Option::is_none")]
+    content_rating_text: Option<RatingText>,
+    #[serde(alias = "contentRating", skip_serializing_if = "Option::is_none")]
+    content_rating: Option<StoryRating>,
+    #[serde(default, skip_serializing)]
+    rating: Option<NestedRating>,
+    #[serde(with = "vote", alias = "num_likes")]
+    likes: Option<u32>,
+    #[serde(with = "vote", alias = "num_dislikes")]
+    dislikes: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "tag::deserialize_tags",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    tags: Vec<Tag>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<GroupLink>,
+    #[serde(borrow, default)]
+    chapters: Vec<ChapterCow<'a>>,
+}
+
+/// Deserializes an optional [`Cow<str>`] borrowing from the input when it can, which
+/// `#[serde(borrow)]` alone doesn't do through an [`Option`].
+fn borrow_option<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<CowStr<'a>>::deserialize(deserializer).map(|text| text.map(|CowStr(text)| text))
+}
+
+impl<'a> TryFrom<StoryCowRepr<'a>> for StoryCow<'a> {
+    type Error = &'static str;
+
+    fn try_from(repr: StoryCowRepr<'a>) -> Result<Self, Self::Error> {
+        let content_rating =
+            rating::from_fields(repr.content_rating, repr.rating, repr.content_rating_text)
+                .ok_or("missing field `content_rating`")?;
+        #[cfg(feature = "derive-full-image")]
+        let full_image = repr
+            .full_image
+            .or_else(|| crate::derive_full_image(repr.image.as_deref()?).map(Cow::Owned));
+        #[cfg(not(feature = "derive-full-image"))]
+        let full_image = repr.full_image;
+
+        Ok(StoryCow {
+            id: repr.id,
+            title: repr.title,
+            url: repr.url,
+            short_description: repr.short_description,
+            description: repr.description,
+            date_modified: repr.date_modified,
+            image: repr.image,
+            full_image,
+            views: repr.views,
+            total_views: repr.total_views,
+            words: repr.words,
+            chapter_count: repr.chapter_count,
+            comments: repr.comments,
+            author: repr.author,
+            status: repr.status,
+            publication_state: repr.publication_state,
+            language: repr.language,
+            content_rating,
+            likes: repr.likes,
+            dislikes: repr.dislikes,
+            tags: repr.tags,
+            groups: repr.groups,
+            chapters: repr.chapters,
+        })
+    }
+}
+
+impl<'a> From<StoryCow<'a>> for StoryCowRepr<'a> {
+    fn from(story: StoryCow<'a>) -> Self {
+        StoryCowRepr {
+            id: story.id,
+            title: story.title,
+            url: story.url,
+            short_description: story.short_description,
+            description: story.description,
+            date_modified: story.date_modified,
+            image: story.image,
+            full_image: story.full_image,
+            views: story.views,
+            total_views: story.total_views,
+            words: story.words,
+            chapter_count: story.chapter_count,
+            comments: story.comments,
+            author: story.author,
+            status: story.status,
+            publication_state: story.publication_state,
+            language: story.language,
+            content_rating_text: Some(RatingText(story.content_rating)),
+            content_rating: Some(story.content_rating),
+            rating: None,
+            likes: story.likes,
+            dislikes: story.dislikes,
+            tags: story.tags,
+            groups: story.groups,
+            chapters: story.chapters,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CowResponse<'a> {
+    #[serde(borrow, default)]
+    story: Option<StoryCow<'a>>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    code: Option<i32>,
+}
+
+/// Deserialize an instance of [`StoryCow`] from an API response String, borrowing from it.
+///
+/// Unlike [`crate::from_str()`], a response that isn't a story nor an error is a deserialization
+/// error.
+///
+/// # Errors
+/// * On a deserialization error (see [`serde_json::from_str()`]).
+/// * The response is an API error.
+/// * The story does not have any chapters, given as an owned [`Story`].
+pub fn from_str_borrowed(input: &str) -> Result<StoryCow<'_>, StoryError> {
+    match serde_json::from_str::<CowResponse>(input)? {
+        CowResponse {
+            story: Some(story),
+            error: None,
+            code: None,
+        } => {
+            if story.chapters.is_empty() && story.chapter_count == 0 {
+                Err(StoryError::Unpublished(Box::new(story.into_owned())))
+            } else {
+                Ok(story)
+            }
+        }
+        CowResponse {
+            story: None,
+            error: Some(message),
+            code,
+        } => Err(StoryError::from_api_message(message, code)),
+        _ => Err(StoryError::Json(serde::de::Error::custom(
+            "expected either a `story` or an `error` key",
+        ))),
+    }
+}
@@ -0,0 +1,270 @@
+//! (De)serialization of the date fields as Unix timestamps in seconds.
+//!
+//! Some mirrors of the API give the timestamps in milliseconds, these are detected by their
+//! magnitude and converted into seconds. With the `flexible-dates` feature RFC 3339 strings are
+//! accepted too.
+
+#[cfg(feature = "chrono")]
+use chrono::{offset::Utc, DateTime, TimeZone};
+use serde::{Deserialize, Deserializer, Serializer};
+#[cfg(feature = "flexible-dates")]
+use serde_with::{DeserializeAs, PickFirst};
+
+/// Timestamps with an absolute value from here on are taken as milliseconds.
+///
+/// In seconds it is a date in the year 5138, while in milliseconds it is one in 1973.
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+fn to_seconds(timestamp: i64) -> i64 {
+    // `unsigned_abs()` as `abs()` overflows on `i64::MIN`.
+    if timestamp.unsigned_abs() >= MILLIS_THRESHOLD as u64 {
+        timestamp / 1000
+    } else {
+        timestamp
+    }
+}
+
+/// [`DeserializeAs`] for a timestamp in seconds or milliseconds.
+#[cfg(feature = "flexible-dates")]
+struct Timestamp;
+
+#[cfg(feature = "flexible-dates")]
+impl<'de> DeserializeAs<'de, i64> for Timestamp {
+    fn deserialize_as<D>(deserializer: D) -> Result<i64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        i64::deserialize(deserializer).map(to_seconds)
+    }
+}
+
+/// [`DeserializeAs`] for an RFC 3339 string, like `2022-12-15T16:41:54Z`.
+#[cfg(feature = "flexible-dates")]
+struct Rfc3339;
+
+#[cfg(feature = "flexible-dates")]
+impl<'de> DeserializeAs<'de, i64> for Rfc3339 {
+    fn deserialize_as<D>(deserializer: D) -> Result<i64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        parse_rfc3339(&text).ok_or_else(|| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(&text),
+                &"an RFC 3339 datetime",
+            )
+        })
+    }
+}
+
+/// Parses exactly `len` ASCII digits.
+#[cfg(feature = "flexible-dates")]
+fn digits(text: &str, len: usize) -> Option<i64> {
+    if text.len() != len || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Parses an RFC 3339 datetime into a Unix timestamp in seconds, ignoring fractions of a second.
+#[cfg(feature = "flexible-dates")]
+fn parse_rfc3339(text: &str) -> Option<i64> {
+    let (date, time) = text.split_once(['T', 't', ' '])?;
+
+    let mut date = date.split('-');
+    let year = digits(date.next()?, 4)?;
+    let month = digits(date.next()?, 2)?;
+    let day = digits(date.next()?, 2)?;
+    if date.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (time, offset) = match time.strip_suffix(['Z', 'z']) {
+        Some(time) => (time, 0),
+        None => {
+            let (time, offset) = time.split_at(time.rfind(['+', '-'])?);
+            let (hours, minutes) = offset[1..].split_once(':')?;
+            let seconds = digits(hours, 2)? * 3600 + digits(minutes, 2)? * 60;
+            (
+                time,
+                if offset.starts_with('-') {
+                    -seconds
+                } else {
+                    seconds
+                },
+            )
+        }
+    };
+    let time = match time.split_once('.') {
+        Some((time, fraction))
+            if !fraction.is_empty() && fraction.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            time
+        }
+        Some(_) => return None,
+        None => time,
+    };
+
+    let mut time = time.split(':');
+    let hour = digits(time.next()?, 2)?;
+    let minute = digits(time.next()?, 2)?;
+    let second = digits(time.next()?, 2)?;
+    if time.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Civil date to days, from Howard Hinnant's `days_from_civil`.
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Some(days * 86400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// Deserializes a timestamp, converting it into seconds.
+fn deserialize_seconds<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[cfg(feature = "flexible-dates")]
+    return PickFirst::<(Timestamp, Rfc3339)>::deserialize_as(deserializer);
+    #[cfg(not(feature = "flexible-dates"))]
+    return i64::deserialize(deserializer).map(to_seconds);
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn deserialize<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_seconds(deserializer)
+}
+
+#[cfg(not(feature = "chrono"))]
+pub fn serialize<S>(timestamp: &i64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_i64(*timestamp)
+}
+
+#[cfg(feature = "chrono")]
+pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let timestamp = deserialize_seconds(deserializer)?;
+    Utc.timestamp_opt(timestamp, 0).single().ok_or_else(|| {
+        serde::de::Error::custom(format_args!("timestamp out of range: {timestamp}"))
+    })
+}
+
+#[cfg(feature = "chrono")]
+pub fn serialize<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_i64(date.timestamp())
+}
+
+/// Largest timestamp that isn't taken as milliseconds when deserialized.
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub(crate) const MAX_SECONDS: i64 = MILLIS_THRESHOLD - 1;
+
+/// A [`Date`](crate::Date) from a timestamp between `0` and [`MAX_SECONDS`].
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub(crate) fn from_seconds(timestamp: i64) -> crate::Date {
+    #[cfg(not(feature = "chrono"))]
+    return timestamp;
+    #[cfg(feature = "chrono")]
+    return Utc.timestamp_opt(timestamp, 0).unwrap();
+}
+
+/// Generates a [`Date`](crate::Date) that isn't taken as milliseconds when deserialized.
+#[cfg(feature = "arbitrary")]
+pub fn arbitrary(unstructured: &mut arbitrary::Unstructured) -> arbitrary::Result<crate::Date> {
+    unstructured.int_in_range(0..=MAX_SECONDS).map(from_seconds)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{deserialize as deserialize_date, serialize as serialize_date};
+
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Deserialize, Serialize)]
+    struct Dates {
+        #[serde(
+            deserialize_with = "deserialize_date",
+            serialize_with = "serialize_date"
+        )]
+        date_modified: crate::Date,
+    }
+
+    #[test]
+    fn serde_seconds() {
+        let value = json!({ "date_modified": 1671122514 });
+        let dates: Dates = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(crate::timestamp(&dates.date_modified), 1671122514);
+
+        let serialized_value = serde_json::to_value(dates).unwrap();
+        assert_eq!(serialized_value, value);
+    }
+
+    #[test]
+    fn deserialize_millis() {
+        let value = json!({ "date_modified": 1671122514123_i64 });
+        let dates: Dates = serde_json::from_value(value).unwrap();
+        assert_eq!(crate::timestamp(&dates.date_modified), 1671122514);
+
+        let serialized_value = serde_json::to_value(dates).unwrap();
+        assert_eq!(serialized_value, json!({ "date_modified": 1671122514 }));
+    }
+
+    #[test]
+    fn deserialize_extremes() {
+        for timestamp in [i64::MIN, i64::MAX] {
+            let value = json!({ "date_modified": timestamp });
+            let result = serde_json::from_value::<Dates>(value);
+            #[cfg(not(feature = "chrono"))]
+            assert_eq!(
+                crate::timestamp(&result.unwrap().date_modified),
+                timestamp / 1000
+            );
+            #[cfg(feature = "chrono")]
+            assert!(result.is_err());
+        }
+    }
+
+    #[cfg(feature = "flexible-dates")]
+    #[test]
+    fn deserialize_rfc3339() {
+        for (text, timestamp) in [
+            ("2022-12-15T16:41:54Z", 1671122514),
+            ("2022-12-15T16:41:54.123Z", 1671122514),
+            ("2022-12-15 18:41:54+02:00", 1671122514),
+            ("2022-12-15T12:41:54-04:00", 1671122514),
+            ("2000-02-29T00:00:00Z", 951782400),
+            ("1969-12-31T23:59:59Z", -1),
+        ] {
+            let dates: Dates = serde_json::from_value(json!({ "date_modified": text })).unwrap();
+            assert_eq!(crate::timestamp(&dates.date_modified), timestamp, "{text}");
+        }
+
+        for text in [
+            "2022-12-15",
+            "2022-13-15T16:41:54Z",
+            "2022-12-15T16:41:54",
+            "yesterday",
+        ] {
+            let result = serde_json::from_value::<Dates>(json!({ "date_modified": text }));
+            assert!(result.is_err(), "{text}");
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{Story, StoryRating, StoryStatus};
+
+/// Changes of a story between two snapshots of it, given by [`Story::diff_since()`].
+///
+/// Transitions are given as `(previous, current)` pairs, like to notify that a story went from
+/// [`Incomplete`](StoryStatus::Incomplete) to [`Complete`](StoryStatus::Complete).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StoryDiff {
+    /// See [`Story::words_added_since()`].
+    pub words_added: i64,
+    /// See [`Story::chapters_added_since()`].
+    pub chapters_added: i64,
+    /// The previous and current [`content_rating`](Story::content_rating), if it changed.
+    pub rating_change: Option<(StoryRating, StoryRating)>,
+    /// The previous and current [`status`](Story::status), if it changed.
+    pub status_change: Option<(StoryStatus, StoryStatus)>,
+}
+
+impl StoryDiff {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.words_added == 0
+            && self.chapters_added == 0
+            && self.rating_change.is_none()
+            && self.status_change.is_none()
+    }
+
+    /// Whether the [`status_change`](StoryDiff::status_change) isn't a plausible one, see
+    /// [`StoryStatus::can_transition_to()`].
+    pub fn has_suspicious_status_change(&self) -> bool {
+        self.status_change
+            .is_some_and(|(previous, current)| !previous.can_transition_to(current))
+    }
+}
+
+impl Story {
+    /// Changes of the story since the `previous` snapshot of it.
+    pub fn diff_since(&self, previous: &Story) -> StoryDiff {
+        StoryDiff {
+            words_added: self.words_added_since(previous),
+            chapters_added: self.chapters_added_since(previous),
+            rating_change: (self.content_rating != previous.content_rating)
+                .then_some((previous.content_rating, self.content_rating)),
+            status_change: (self.status != previous.status)
+                .then_some((previous.status, self.status)),
+        }
+    }
+}
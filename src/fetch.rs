@@ -0,0 +1,599 @@
+use std::{error::Error as StdError, io, time::Duration};
+
+use thiserror::Error;
+
+use crate::{from_str, CachedStory, Id, Story, StoryError};
+
+/// URL of the Fimfiction story API.
+const API_URL: &str = "https://www.fimfiction.net/api/story.php";
+
+/// URL of the Fimfiction story API for the story with the given `id`.
+pub fn story_api_url(id: Id) -> String {
+    format!("{API_URL}?story={id}")
+}
+
+/// Configuration of the fetch functions of the HTTP backend features.
+#[derive(Debug, Clone)]
+pub struct FetchConfig {
+    /// Maximum time a request can take, from connecting to reading the whole response, or
+    /// `None` for no limit. Exceeding it fails with [`FetchError::Timeout`].
+    ///
+    /// Defaults to 30 seconds.
+    pub timeout: Option<Duration>,
+    /// URL of the story API, to which the `story` query parameter is appended.
+    ///
+    /// Defaults to the one of Fimfiction, only a mirror or a mock server need another.
+    pub api_url: String,
+    /// How many times a story request is retried after an error that
+    /// [is retryable](FetchError::is_retryable).
+    ///
+    /// Defaults to `0`, not retrying.
+    pub retries: u32,
+    /// Time to wait before retrying a story request.
+    ///
+    /// Defaults to 1 second.
+    pub retry_delay: Duration,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        FetchConfig {
+            timeout: Some(Duration::from_secs(30)),
+            api_url: API_URL.to_string(),
+            retries: 0,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl FetchConfig {
+    /// URL of the story API for the story with the given `id`.
+    fn story_url(&self, id: Id) -> String {
+        format!("{}?story={id}", self.api_url)
+    }
+}
+
+/// Represents errors that can occur while fetching a [`Story`] from the Fimfiction story API.
+///
+/// It is the same for every HTTP backend.
+#[derive(Debug, Error)]
+pub enum FetchError {
+    /// The API answered with a non-successful HTTP status code.
+    #[error("HTTP error: status code {0}")]
+    Status(u16),
+
+    /// The request took longer than the [`timeout`](FetchConfig::timeout).
+    #[error("request timed out")]
+    Timeout,
+
+    /// The request couldn't be completed, like on a connection or TLS error.
+    #[error("transport error: {0}")]
+    Transport(Box<dyn StdError + Send + Sync>),
+
+    /// The response body couldn't be read.
+    #[error("i/o error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The response body couldn't be deserialized into a [`Story`].
+    #[error(transparent)]
+    Story(#[from] StoryError),
+
+    /// The response body couldn't be decoded as an image.
+    #[cfg(feature = "cover-dimensions")]
+    #[error("image error: {0}")]
+    Image(#[from] image::ImageError),
+}
+
+impl FetchError {
+    /// Whether retrying the request that caused this error could succeed.
+    ///
+    /// Rate limiting (`429`) and server error status codes, timeouts, transport and I/O errors are
+    /// taken as transient, while a [`Story`](FetchError::Story) error defers to
+    /// [`StoryError::is_retryable()`].
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            FetchError::Status(code) => *code == 429 || (500..600).contains(code),
+            FetchError::Timeout | FetchError::Transport(_) | FetchError::Io(_) => true,
+            FetchError::Story(err) => err.is_retryable(),
+            #[cfg(feature = "cover-dimensions")]
+            FetchError::Image(_) => false,
+        }
+    }
+
+    /// The HTTP status code of the response, if the error was caused by one.
+    ///
+    /// Returns `None` for timeouts, transport, I/O, deserialization and image errors.
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            FetchError::Status(code) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `err` is the timeout of a request.
+fn is_timeout(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+    )
+}
+
+/// Fetch the [`Story`] with the given `id` using [`ureq`] with the [`Default`] [`FetchConfig`].
+///
+/// # Errors
+/// Same as [`fetch_story_ureq_with()`].
+pub fn fetch_story_ureq(id: Id) -> Result<Story, FetchError> {
+    fetch_story_ureq_with(id, &FetchConfig::default())
+}
+
+/// Fetch the [`Story`] with the given `id` using [`ureq`] with the given `config`.
+///
+/// Retryable errors are retried up to [`retries`](FetchConfig::retries) times.
+///
+/// With the `tracing` feature, the request is wrapped in a span recording `id`, the number of the
+/// last `attempt`, its `outcome`, either `"ok"` or `"error"`, and any error.
+///
+/// # Errors
+/// * On any HTTP or transport error.
+/// * On exceeding the [`timeout`](FetchConfig::timeout).
+/// * On any of the errors of [`from_str()`].
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(config), fields(attempt, outcome), err)
+)]
+pub fn fetch_story_ureq_with(id: Id, config: &FetchConfig) -> Result<Story, FetchError> {
+    let mut attempt = 1;
+    loop {
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("attempt", attempt);
+
+        match fetch_story_ureq_once(id, config) {
+            Err(err) if err.is_retryable() && attempt <= config.retries => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(attempt, error = %err, "retrying");
+                std::thread::sleep(config.retry_delay);
+                attempt += 1;
+            }
+            result => {
+                #[cfg(feature = "tracing")]
+                tracing::Span::current()
+                    .record("outcome", if result.is_ok() { "ok" } else { "error" });
+                return result;
+            }
+        }
+    }
+}
+
+/// A single attempt of [`fetch_story_ureq_with()`].
+fn fetch_story_ureq_once(id: Id, config: &FetchConfig) -> Result<Story, FetchError> {
+    let body = get_ureq(&config.story_url(id), config)?
+        .into_string()
+        .map_err(read_error)?;
+    Ok(from_str(&body)?)
+}
+
+/// [`fetch_story_ureq_with()`], recording when the story was fetched to tell when it should be
+/// fetched again.
+///
+/// # Errors
+/// Same as [`fetch_story_ureq_with()`].
+pub fn fetch_cached_story_ureq_with(
+    id: Id,
+    config: &FetchConfig,
+) -> Result<CachedStory, FetchError> {
+    fetch_story_ureq_with(id, config).map(CachedStory::new)
+}
+
+/// Returns the `cached` copy of the story with the given `id` unless it is missing or
+/// [expired](CachedStory::is_expired) after `ttl`, in which case it is fetched again with
+/// [`fetch_cached_story_ureq_with()`].
+///
+/// With the `tracing` feature, this is wrapped in a span recording `id`, `ttl` and whether the
+/// `cache` was a `"hit"`, a `"miss"` or `"expired"`, around the span of any fetch.
+///
+/// # Errors
+/// Same as [`fetch_story_ureq_with()`], only when the story is fetched.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(cached, config), fields(cache), err)
+)]
+pub fn refresh_cached_story_ureq_with(
+    cached: Option<CachedStory>,
+    id: Id,
+    ttl: Duration,
+    config: &FetchConfig,
+) -> Result<CachedStory, FetchError> {
+    let cache = match &cached {
+        None => "miss",
+        Some(cached) if cached.is_expired(ttl) => "expired",
+        Some(_) => "hit",
+    };
+    #[cfg(feature = "tracing")]
+    tracing::Span::current().record("cache", cache);
+
+    match cached {
+        Some(cached) if cache == "hit" => Ok(cached),
+        _ => fetch_cached_story_ureq_with(id, config),
+    }
+}
+
+/// Send a GET request to `url` with the timeout of `config`.
+fn get_ureq(url: &str, config: &FetchConfig) -> Result<ureq::Response, FetchError> {
+    let mut agent = ureq::AgentBuilder::new();
+    if let Some(timeout) = config.timeout {
+        agent = agent.timeout(timeout);
+    }
+
+    agent.build().get(url).call().map_err(|err| match err {
+        ureq::Error::Status(code, _) => FetchError::Status(code),
+        ureq::Error::Transport(transport) => {
+            let timed_out = transport
+                .source()
+                .and_then(|source| source.downcast_ref::<io::Error>())
+                .is_some_and(is_timeout);
+            if timed_out {
+                FetchError::Timeout
+            } else {
+                FetchError::Transport(Box::new(transport))
+            }
+        }
+    })
+}
+
+/// The error for a failure reading a response body.
+fn read_error(err: io::Error) -> FetchError {
+    if is_timeout(&err) {
+        FetchError::Timeout
+    } else {
+        FetchError::Io(err)
+    }
+}
+
+/// Largest cover image [`Story::fetch_cover_dimensions()`] reads, in bytes.
+#[cfg(feature = "cover-dimensions")]
+const MAX_COVER_BYTES: u64 = 16 * 1024 * 1024;
+
+#[cfg(feature = "cover-dimensions")]
+impl Story {
+    /// Fetch the cover of the story and read its width and height, in pixels, like to reserve
+    /// space for it with the right aspect ratio.
+    ///
+    /// The full size [`full_image`](Story::full_image) is preferred over the thumbnail
+    /// [`image`](Story::image). Returns `None` if the story doesn't have a cover.
+    ///
+    /// # Errors
+    /// * On any HTTP or transport error.
+    /// * On exceeding the [`timeout`](FetchConfig::timeout).
+    /// * On an image that can't be decoded.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(id = self.id), err)
+    )]
+    pub fn fetch_cover_dimensions(
+        &self,
+        config: &FetchConfig,
+    ) -> Result<Option<(u32, u32)>, FetchError> {
+        use std::io::{Cursor, Read};
+
+        let Some(url) = self.full_image.as_ref().or(self.image.as_ref()) else {
+            return Ok(None);
+        };
+
+        let mut bytes = Vec::new();
+        get_ureq(url, config)?
+            .into_reader()
+            .take(MAX_COVER_BYTES)
+            .read_to_end(&mut bytes)
+            .map_err(read_error)?;
+
+        let dimensions = image::ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()?
+            .into_dimensions()?;
+        Ok(Some(dimensions))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn api_url() {
+        assert_eq!(
+            story_api_url(428991),
+            "https://www.fimfiction.net/api/story.php?story=428991"
+        );
+    }
+
+    #[test]
+    fn retryable_errors() {
+        assert!(FetchError::Status(429).is_retryable());
+        assert!(FetchError::Status(503).is_retryable());
+        assert!(!FetchError::Status(404).is_retryable());
+        assert!(FetchError::Io(io::ErrorKind::UnexpectedEof.into()).is_retryable());
+        assert!(!FetchError::Story(StoryError::InvalidId).is_retryable());
+    }
+
+    #[test]
+    fn status_code() {
+        assert_eq!(FetchError::Status(404).status_code(), Some(404));
+        assert_eq!(FetchError::Status(503).status_code(), Some(503));
+        assert_eq!(
+            FetchError::Io(io::ErrorKind::UnexpectedEof.into()).status_code(),
+            None
+        );
+        assert_eq!(FetchError::Story(StoryError::InvalidId).status_code(), None);
+    }
+
+    #[test]
+    fn default_config_url() {
+        assert_eq!(
+            FetchConfig::default().story_url(428991),
+            story_api_url(428991)
+        );
+    }
+
+    #[test]
+    fn timeout() {
+        use std::{io::Read, net::TcpListener, thread};
+
+        // Reads the request and then never answers until the client gives up.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0; 1024];
+            while stream.read(&mut buffer).is_ok_and(|read| read > 0) {}
+        });
+
+        let config = FetchConfig {
+            timeout: Some(Duration::from_millis(200)),
+            api_url: format!("http://{address}/api/story.php"),
+            ..FetchConfig::default()
+        };
+        let err = fetch_story_ureq_with(428991, &config).unwrap_err();
+        assert!(matches!(err, FetchError::Timeout), "{err:?}");
+        assert!(err.is_retryable());
+
+        server.join().unwrap();
+    }
+
+    /// Subscriber keeping the last value recorded for each field of any of its spans, which are
+    /// told apart only to know the current one.
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct SpanFields {
+        fields: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<String, String>>>,
+        /// Metadata of every span, the one with ID `n` at `n - 1`.
+        spans: std::sync::Arc<std::sync::Mutex<Vec<&'static tracing::Metadata<'static>>>>,
+        /// IDs of the entered spans, the current one last.
+        entered: std::sync::Arc<std::sync::Mutex<Vec<u64>>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for SpanFields {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            let value = format!("{value:?}");
+            self.fields
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), value);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for SpanFields {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(span.metadata());
+            span.record(&mut self.clone());
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, _: &tracing::Event<'_>) {}
+
+        fn enter(&self, span: &tracing::span::Id) {
+            self.entered.lock().unwrap().push(span.into_u64());
+        }
+
+        fn exit(&self, _: &tracing::span::Id) {
+            self.entered.lock().unwrap().pop();
+        }
+
+        fn current_span(&self) -> tracing_core::span::Current {
+            match self.entered.lock().unwrap().last() {
+                Some(&id) => tracing_core::span::Current::new(
+                    tracing::span::Id::from_u64(id),
+                    self.spans.lock().unwrap()[id as usize - 1],
+                ),
+                None => tracing_core::span::Current::none(),
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn span_fields() {
+        use std::{
+            io::{Read, Write},
+            net::TcpListener,
+            thread,
+        };
+
+        let body = crate::to_string(Story {
+            id: 1,
+            chapter_count: 1,
+            ..Story::default()
+        })
+        .unwrap();
+
+        // Answers the first request with a server error and the second with the story.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            for response in [
+                "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string(),
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                ),
+            ] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = [0; 1024];
+                let _ = stream.read(&mut buffer).unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let subscriber = SpanFields::default();
+        let fields = subscriber.fields.clone();
+        let config = FetchConfig {
+            api_url: format!("http://{address}/api/story.php"),
+            retries: 1,
+            retry_delay: Duration::ZERO,
+            ..FetchConfig::default()
+        };
+        let story =
+            tracing::subscriber::with_default(subscriber, || fetch_story_ureq_with(1, &config))
+                .unwrap();
+        assert_eq!(story.id, 1);
+
+        let fields = fields.lock().unwrap();
+        assert_eq!(fields["id"], "1");
+        assert_eq!(fields["attempt"], "2");
+        assert_eq!(fields["outcome"], "\"ok\"");
+
+        server.join().unwrap();
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn cache_span_fields() {
+        use std::{
+            io::{Read, Write},
+            net::TcpListener,
+            thread,
+        };
+
+        let story = Story {
+            id: 1,
+            chapter_count: 1,
+            ..Story::default()
+        };
+        let body = crate::to_string(story.clone()).unwrap();
+
+        // Answers the two requests of a miss and an expired copy, but not the one of a hit.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = [0; 1024];
+                let _ = stream.read(&mut buffer).unwrap();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let config = FetchConfig {
+            api_url: format!("http://{address}/api/story.php"),
+            ..FetchConfig::default()
+        };
+        let ttl = Duration::from_secs(3600);
+        let refresh = |cached: Option<CachedStory>| {
+            let subscriber = SpanFields::default();
+            let fields = subscriber.fields.clone();
+            let cached = tracing::subscriber::with_default(subscriber, || {
+                refresh_cached_story_ureq_with(cached, 1, ttl, &config)
+            })
+            .unwrap();
+            let cache = fields.lock().unwrap()["cache"].clone();
+            (cached, cache)
+        };
+
+        let fresh = CachedStory::new(story.clone());
+        let (cached, cache) = refresh(Some(fresh.clone()));
+        assert_eq!(cache, "\"hit\"");
+        assert_eq!(cached.fetched_at, fresh.fetched_at);
+
+        let (cached, cache) = refresh(None);
+        assert_eq!(cache, "\"miss\"");
+        assert_eq!(cached.story.id, 1);
+
+        let expired = CachedStory {
+            story,
+            fetched_at: 0,
+        };
+        let (cached, cache) = refresh(Some(expired));
+        assert_eq!(cache, "\"expired\"");
+        assert!(cached.fetched_at > 0);
+
+        server.join().unwrap();
+    }
+
+    #[cfg(feature = "cover-dimensions")]
+    #[test]
+    fn cover_dimensions() {
+        use std::{
+            io::{Cursor, Read, Write},
+            net::TcpListener,
+            thread,
+        };
+
+        let mut png = Vec::new();
+        image::RgbImage::new(3, 2)
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        // Answers a single request with the image.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0; 1024];
+            let _ = stream.read(&mut buffer).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\n\r\n",
+                png.len()
+            )
+            .unwrap();
+            stream.write_all(&png).unwrap();
+        });
+
+        let mut story = Story {
+            id: 1,
+            chapter_count: 1,
+            ..Story::default()
+        };
+        assert_eq!(
+            story
+                .fetch_cover_dimensions(&FetchConfig::default())
+                .unwrap(),
+            None
+        );
+
+        story.full_image = Some(format!("http://{address}/cover.png"));
+        let dimensions = story
+            .fetch_cover_dimensions(&FetchConfig::default())
+            .unwrap();
+        assert_eq!(dimensions, Some((3, 2)));
+
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Id, Story};
+
+/// A group a [`Story`] was posted to.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct GroupLink {
+    /// Group's ID.
+    pub id: Id,
+    /// Name of the group.
+    pub name: String,
+    /// Fimfiction URL to the group.
+    pub url: String,
+}
+
+impl Story {
+    /// Whether the story was posted to the group with the given ID.
+    pub fn is_in_group(&self, id: Id) -> bool {
+        self.groups.iter().any(|group| group.id == id)
+    }
+}
@@ -17,35 +17,226 @@
 //!
 //! Changes all date fields to use `DateTime<Utc>` instead of an `i64`.
 //!
+//! In both cases timestamps given in milliseconds instead of seconds are detected and converted
+//! into seconds.
+//!
+//! # The `arbitrary` feature
+//!
+//! Implements [`Arbitrary`](https://docs.rs/arbitrary/1/arbitrary/trait.Arbitrary.html) for
+//! [`Story`] and the types it contains, always generating values that survive a serialization
+//! round trip.
+//!
+//! # The `cover-dimensions` feature
+//!
+//! Enables the `ureq` feature and adds `Story::fetch_cover_dimensions()` to fetch the cover of a
+//! story and read its size with the [`image`](https://docs.rs/image/0.25) crate.
+//!
+//! # The `cow` feature
+//!
+//! Adds the `cow` module with variants of [`Story`] and the types it contains that borrow their
+//! text from the response when possible.
+//!
+//! # The `derive-full-image` feature
+//!
+//! Fills in a missing [`full_image`](Story::full_image) from the [`image`](Story::image)
+//! thumbnail whenever a [`Story`] is deserialized, see [`Story::full_image_or_derived()`].
+//!
+//! The derived URL is serialized like any other, so a response without `full_image` doesn't
+//! survive a round trip, like in `assert_roundtrip()`.
+//!
+//! # The `flexible-dates` feature
+//!
+//! Makes the date fields also accept RFC 3339 strings, like `2022-12-15T16:41:54Z`, with the
+//! format detected on each value. They are still serialized as timestamps in seconds.
+//!
+//! # The `log` feature
+//!
+//! Logs surprising inputs with the [`log`](https://docs.rs/log/0.4) crate: a debug message for
+//! API error messages without their own [`StoryError`] variant and a warning for each unknown
+//! story field.
+//!
+//! # The `opds` feature
+//!
+//! Adds `Story::to_opds_entry()` to get a story as an Atom entry for
+//! [OPDS](https://specs.opds.io/) catalogs.
+//!
+//! # The `opf` feature
+//!
+//! Adds `Story::to_opf()` to get a story as an OPF package document, like the `metadata.opf`
+//! files used by [Calibre](https://calibre-ebook.com/).
+//!
+//! # The `proptest` feature
+//!
+//! Adds the `proptest` module with strategies generating valid stories.
+//!
+//! # The `rusqlite` feature
+//!
+//! Adds `Story::bind_to()` and `Story::from_row()` to store the scalar fields of a story in
+//! SQLite with [`rusqlite`](https://docs.rs/rusqlite/0.40), with the columns listed in the
+//! `sqlite` module.
+//!
+//! # The `schemars` feature
+//!
+//! Implements [`JsonSchema`](https://docs.rs/schemars/0.8/schemars/trait.JsonSchema.html) for
+//! [`Story`] and the types it contains, describing their serialized form, and adds
+//! `Story::as_json_schema()`.
+//!
+//! # The `test-util` feature
+//!
+//! Adds `assert_roundtrip()` to check that your own sample responses survive a deserialization
+//! and serialization round trip.
+//!
+//! # The `tracing` feature
+//!
+//! Wraps the fetch functions of the HTTP backend features in
+//! [`tracing`](https://docs.rs/tracing/0.1) spans.
+//!
+//! # The `ureq` feature
+//!
+//! Adds `fetch_story_ureq()` to fetch and deserialize a story with a blocking [`ureq`][ureq]
+//! client, and `fetch_story_ureq_with()` to set its timeout through a `FetchConfig`.
+//! `fetch_cached_story_ureq_with()` also records when it was fetched in a [`CachedStory`], which
+//! `refresh_cached_story_ureq_with()` only fetches again once it expired.
+//!
 //! [fimfiction]: https://www.fimfiction.net/
+//! [ureq]: https://docs.rs/ureq/2
 #![deny(missing_docs, missing_debug_implementations, dead_code)]
 
 #[cfg(feature = "chrono")]
-use chrono::{offset::Utc, DateTime};
-use serde::{Deserialize, Serialize};
+use chrono::{offset::Utc, DateTime, TimeZone};
+use serde::{de, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    hash::Hasher,
+    time::Duration,
+};
 use thiserror::Error;
 
-mod rating;
+pub mod approx;
+mod author;
+mod bracket;
+mod cache;
+mod card;
+mod chapters;
+#[cfg(feature = "cow")]
+pub mod cow;
+mod date;
+mod diff;
+#[cfg(feature = "ureq")]
+mod fetch;
+mod fingerprint;
+mod group;
+mod markdown;
+#[cfg(feature = "opds")]
+mod opds;
+#[cfg(feature = "opf")]
+mod opf;
+pub mod prelude;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+pub mod rating;
+mod raw;
+mod repr;
+mod search;
+mod sort;
+#[cfg(feature = "rusqlite")]
+pub mod sqlite;
 mod status;
+mod tag;
+mod validate;
+mod views;
 mod vote;
+#[cfg(any(feature = "opds", feature = "opf"))]
+mod xml;
 
+pub use author::Author;
+pub use bracket::WordBracket;
+pub use cache::CachedStory;
+pub use card::StoryCard;
+pub use chapters::ChapterList;
+pub use diff::StoryDiff;
+#[cfg(feature = "ureq")]
+pub use fetch::{
+    fetch_cached_story_ureq_with, fetch_story_ureq, fetch_story_ureq_with,
+    refresh_cached_story_ureq_with, story_api_url, FetchConfig, FetchError,
+};
+pub use group::GroupLink;
 pub use rating::StoryRating;
-pub use status::StoryStatus;
+pub use raw::{from_str_raw, RawStory};
+pub use search::search;
+pub use sort::{SortBy, SortKey};
+pub use status::{PublicationState, StoryStatus};
+pub use tag::{Tag, TagKind};
+pub use validate::ValidationError;
+pub use views::ViewsReport;
 
 /// A Fimfiction ID.
 pub type Id = u32;
 
-/// Container struct of the author response given by the Fimfiction story API.
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct Author {
-    /// Author's ID.
-    pub id: Id,
-    /// Username of the author.
-    pub name: String,
+const DEFAULT_WORDS_PER_PAGE: u32 = 250;
+const DEFAULT_NARRATION_WORDS_PER_MINUTE: u32 = 150;
+
+fn narration_duration(words: u64, words_per_minute: u32) -> Duration {
+    match words_per_minute {
+        0 => Duration::ZERO,
+        words_per_minute => {
+            let millis = u128::from(words) * 60_000 / u128::from(words_per_minute);
+            Duration::from_millis(u64::try_from(millis).unwrap_or(u64::MAX))
+        }
+    }
+}
+
+#[cfg(not(feature = "chrono"))]
+type Date = i64;
+#[cfg(feature = "chrono")]
+type Date = DateTime<Utc>;
+
+#[cfg(not(feature = "chrono"))]
+fn timestamp(date: &Date) -> i64 {
+    *date
+}
+
+#[cfg(feature = "chrono")]
+fn timestamp(date: &Date) -> i64 {
+    date.timestamp()
+}
+
+/// Formats `value` with `separator` between every `group` digits, counting from the right.
+fn group_digits(value: u64, separator: char, group: usize) -> String {
+    let digits = value.to_string();
+    if group == 0 {
+        return digits;
+    }
+
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / group * 4);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(group) {
+            formatted.push(separator);
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// The full size cover URL for the thumbnail URL `image`, if it has the size suffix of the CDN.
+fn derive_full_image(image: &str) -> Option<String> {
+    image
+        .strip_suffix("-medium")
+        .map(|base| format!("{base}-full"))
+}
+
+fn estimated_pages(words: u64, words_per_page: u32) -> u64 {
+    match words_per_page {
+        0 => 0,
+        words_per_page => words.div_ceil(words_per_page.into()),
+    }
 }
 
 /// Container struct for all chapter response data given by the Fimfiction story API.
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Chapter {
     /// Chapter's ID.
     pub id: Id,
@@ -53,22 +244,90 @@ pub struct Chapter {
     pub title: String,
     /// The amount of words the chapter has.
     pub words: u64,
-    /// The amount of views the chapter has.
+    /// The amount of views the chapter has, `0` if hidden.
+    #[serde(with = "vote::or_zero")]
+    #[cfg_attr(feature = "schemars", schemars(with = "u32"))]
     pub views: u32,
     /// Fimfiction URL to the story's chapter.
     pub link: String,
 
     #[cfg(not(feature = "chrono"))]
+    #[serde(with = "date", alias = "dateModified")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = date::arbitrary))]
+    #[cfg_attr(feature = "schemars", schemars(with = "i64"))]
     /// Last chapter update timestamp.
     pub date_modified: i64,
     #[cfg(feature = "chrono")]
-    #[serde(with = "chrono::serde::ts_seconds")]
+    #[serde(with = "date", alias = "dateModified")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = date::arbitrary))]
+    #[cfg_attr(feature = "schemars", schemars(with = "i64"))]
     /// Last chapter update datetime.
     pub date_modified: DateTime<Utc>,
 }
 
+impl Chapter {
+    /// Approximate amount of pages the chapter would take up with `words_per_page` words on each
+    /// page, rounded up.
+    ///
+    /// Returns `0` if `words_per_page` is `0`.
+    pub fn estimated_pages(&self, words_per_page: u32) -> u64 {
+        estimated_pages(self.words, words_per_page)
+    }
+
+    /// [`Chapter::estimated_pages()`] with 250 words per page.
+    pub fn estimated_pages_default(&self) -> u64 {
+        self.estimated_pages(DEFAULT_WORDS_PER_PAGE)
+    }
+
+    /// Approximate duration of the chapter narrated at `words_per_minute`.
+    ///
+    /// Returns a zero duration if `words_per_minute` is `0`.
+    pub fn audiobook_duration(&self, words_per_minute: u32) -> Duration {
+        narration_duration(self.words, words_per_minute)
+    }
+
+    /// [`Chapter::audiobook_duration()`] at 150 words per minute.
+    pub fn audiobook_duration_default(&self) -> Duration {
+        self.audiobook_duration(DEFAULT_NARRATION_WORDS_PER_MINUTE)
+    }
+
+    /// Whether the chapter has more than `threshold` words.
+    pub fn is_long(&self, threshold: u64) -> bool {
+        self.words > threshold
+    }
+
+    /// [`date_modified`](Chapter::date_modified) in the timezone `tz`, like to group chapters
+    /// by the calendar date of the reader.
+    #[cfg(feature = "chrono")]
+    pub fn modified_date_in<Tz: TimeZone>(&self, tz: &Tz) -> DateTime<Tz> {
+        self.date_modified.with_timezone(tz)
+    }
+
+    /// Estimate of the memory taken by the chapter, the size of the struct plus the length of its
+    /// strings.
+    pub fn approximate_size_bytes(&self) -> usize {
+        std::mem::size_of::<Chapter>() + self.title.len() + self.link.len()
+    }
+
+    /// Whether both chapters have the same content: ID, title, amount of words and last update.
+    ///
+    /// Unlike a full comparison, their views are ignored.
+    pub fn content_eq(&self, other: &Chapter) -> bool {
+        self.id == other.id
+            && self.title == other.title
+            && self.words == other.words
+            && self.date_modified == other.date_modified
+    }
+}
+
 /// Container struct for all relevant story response data given by the Fimfiction story API.
+///
+/// The [`Default`] story is an empty one, to build up field by field: an ID of `0`, empty text,
+/// counts of `0`, no cover, votes or chapters, [`Incomplete`](StoryStatus::Incomplete), rated for
+/// [`Everyone`](StoryRating::Everyone) and last updated on the Unix epoch.
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(try_from = "repr::StoryRepr", into = "repr::StoryRepr")]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Story {
     /// Unique story ID.
     pub id: Id,
@@ -83,230 +342,1395 @@ pub struct Story {
     pub description: String,
 
     #[cfg(not(feature = "chrono"))]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = date::arbitrary))]
     /// Last story update timestamp.
     pub date_modified: i64,
     #[cfg(feature = "chrono")]
-    #[serde(with = "chrono::serde::ts_seconds")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = date::arbitrary))]
     /// Last story update datetime.
     pub date_modified: DateTime<Utc>,
 
     /// Story cover image in thumbnail size if any.
+    ///
+    /// Both a `null` value and a missing key are deserialized as `None`, which is always
+    /// serialized back as `null`.
     pub image: Option<String>,
     /// Story cover image in full size if any.
+    ///
+    /// Same as [`image`](Story::image), `null` and a missing key are both `None`.
     pub full_image: Option<String>,
-    /// The views the story has.
+    /// The views the story has, `0` if hidden.
     pub views: u32,
-    /// The total views the story has.
+    /// The total views the story has, `0` if hidden.
     pub total_views: u32,
     /// The amount of words the story has.
     pub words: u64,
     /// The amount of chapters the story has.
     pub chapter_count: u64,
-    /// The amount of comments the story has.
+    /// The amount of comments the story has, `0` if missing.
     pub comments: u32,
     /// Author of the story.
+    ///
+    /// Anonymized stories may not have one, in which case it is the unknown
+    /// [`Default`](Author::default) author.
     pub author: Author,
     /// Story completion status.
     pub status: StoryStatus,
-    /// Rating of the story as a String.
-    ///
-    /// This is needed to get back the complete original response content from serializing this
-    /// struct.
-    #[serde(with = "rating::serde_text")]
-    content_rating_text: StoryRating,
+    /// Story publication state, if given by the API.
+    pub publication_state: Option<PublicationState>,
+    /// Language the story is written in, like `English`, if given by the API.
+    pub language: Option<String>,
     /// Rating given to the story.
+    ///
+    /// Deserialized either from the flat `content_rating`/`content_rating_text` pair, where
+    /// either can be missing, or from a nested `rating: {value, name}` object, and always
+    /// serialized as the flat pair. When more than one is given `content_rating` is used, then
+    /// the nested object, without checking that they agree.
     pub content_rating: StoryRating,
     /// The amount of likes the story has, if not disabled.
-    #[serde(with = "vote")]
     pub likes: Option<u32>,
     /// The amount of dislikes the story has, if not disabled.
-    #[serde(with = "vote")]
     pub dislikes: Option<u32>,
+    /// Tags of the story, if given by the API.
+    ///
+    /// Can also be deserialized from a comma-separated string of tag names, which is serialized
+    /// back as an array of [`Other`](TagKind::Other) tags.
+    pub tags: Vec<Tag>,
+    /// Groups the story was posted to, if given by the API.
+    pub groups: Vec<GroupLink>,
     /// Chapters of the story.
-    #[serde(default)]
-    pub chapters: Vec<Chapter>,
+    pub chapters: ChapterList,
 }
 
-/// Represents errors that can occur while deserializing a [`Story`].
-#[derive(Debug, Error)]
-pub enum StoryError {
-    /// A deserialization error.
-    #[error("json deserialization error: {0}")]
-    Json(#[from] serde_json::Error),
+impl Default for Story {
+    fn default() -> Self {
+        Story {
+            id: 0,
+            title: String::new(),
+            url: String::new(),
+            short_description: String::new(),
+            description: String::new(),
+            date_modified: Date::default(),
+            image: None,
+            full_image: None,
+            views: 0,
+            total_views: 0,
+            words: 0,
+            chapter_count: 0,
+            comments: 0,
+            author: Author::default(),
+            status: StoryStatus::Incomplete,
+            publication_state: None,
+            language: None,
+            content_rating: StoryRating::Everyone,
+            likes: None,
+            dislikes: None,
+            tags: Vec::new(),
+            groups: Vec::new(),
+            chapters: ChapterList::default(),
+        }
+    }
+}
 
-    /// The story is not public, content is incomplete.
-    #[error("unpublised error: incomplete content")]
-    Unpublished(Story),
+impl Story {
+    /// JSON schema of a serialized [`Story`].
+    #[cfg(feature = "schemars")]
+    pub fn as_json_schema() -> schemars::schema::RootSchema {
+        schemars::schema_for!(Story)
+    }
 
-    /// Alias for `"Invalid story id"` API error message.
-    #[error("API error: Invalid story ID")]
-    InvalidId,
+    /// Approximate amount of pages the story would take up with `words_per_page` words on each
+    /// page, rounded up.
+    ///
+    /// Returns `0` if `words_per_page` is `0`.
+    pub fn estimated_pages(&self, words_per_page: u32) -> u64 {
+        estimated_pages(self.words, words_per_page)
+    }
 
-    /// An API error message which doesn't have its own variant.
-    #[error("API error: {0}")]
-    Api(String),
-}
+    /// [`Story::estimated_pages()`] with 250 words per page.
+    pub fn estimated_pages_default(&self) -> u64 {
+        self.estimated_pages(DEFAULT_WORDS_PER_PAGE)
+    }
 
-/// Represents the different responses that the Fimfiction story API can return.
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Response {
-    /// The API returned a [`Story`].
-    Story(Story),
-    /// The API returned an error.
-    Error(String),
-}
+    /// Approximate duration of the story narrated at `words_per_minute`.
+    ///
+    /// Returns a zero duration if `words_per_minute` is `0`.
+    pub fn audiobook_duration(&self, words_per_minute: u32) -> Duration {
+        narration_duration(self.words, words_per_minute)
+    }
 
-/// Deserialize an instance of [`Story`] from an API response String.
-///
-/// # Errors
-/// * On a deserialization error (see [`serde_json::from_str()`]).
-/// * The resulting [`Response`] is of the [`Error`](Response::Error) variant.
-/// * The resulting [`Story`] does not have any chapters.
-pub fn from_str(input: &str) -> Result<Story, StoryError> {
-    let res = serde_json::from_str::<Response>(input)?;
-
-    match res {
-        Response::Story(story) => {
-            if story.chapters.is_empty() && story.chapter_count == 0 {
-                Err(StoryError::Unpublished(story))
-            } else {
-                Ok(story)
+    /// [`Story::audiobook_duration()`] at 150 words per minute.
+    pub fn audiobook_duration_default(&self) -> Duration {
+        self.audiobook_duration(DEFAULT_NARRATION_WORDS_PER_MINUTE)
+    }
+
+    /// [`Chapter::audiobook_duration_default()`] of each chapter, in reading order.
+    pub fn chapter_audiobook_durations(&self) -> Vec<Duration> {
+        self.chapter_audiobook_durations_with(DEFAULT_NARRATION_WORDS_PER_MINUTE)
+    }
+
+    /// [`Chapter::audiobook_duration()`] of each chapter at `words_per_minute`, in reading order.
+    pub fn chapter_audiobook_durations_with(&self, words_per_minute: u32) -> Vec<Duration> {
+        self.chapters
+            .iter()
+            .map(|chapter| chapter.audiobook_duration(words_per_minute))
+            .collect()
+    }
+
+    /// [`words`](Story::words) with commas between groups of thousands, like `275,949`.
+    pub fn words_formatted(&self) -> String {
+        self.words_formatted_with(',', 3)
+    }
+
+    /// [`words`](Story::words) with `separator` between every `group` digits, like `275.949` or
+    /// `275 949` for other locales.
+    ///
+    /// A `group` of `0` leaves the digits ungrouped.
+    pub fn words_formatted_with(&self, separator: char, group: usize) -> String {
+        group_digits(self.words, separator, group)
+    }
+
+    /// The [`id`](Story::id) of the story in lowercase base 36, like `970f` for `428991`, for
+    /// terse URLs or file names.
+    ///
+    /// [`parse_short_id()`] turns it back into the ID.
+    pub fn short_id(&self) -> String {
+        let mut id = self.id;
+        let mut digits = Vec::new();
+        loop {
+            digits.push(char::from_digit(id % 36, 36).expect("remainder is a base 36 digit"));
+            id /= 36;
+            if id == 0 {
+                break;
             }
         }
-        Response::Error(err) => Err(match err.as_str() {
-            "Invalid story id" => StoryError::InvalidId,
-            _ => StoryError::Api(err),
-        }),
+        digits.into_iter().rev().collect()
     }
-}
 
-/// Serialize a [`Story`] as a Fimfiction story response String.
-///
-/// A convenience function for wrapping `story` into a [`Response`] and getting the string from
-/// [`serde_json::to_string()`].
-pub fn to_string(story: Story) -> Result<String, serde_json::Error> {
-    serde_json::to_string(&Response::Story(story))
-}
+    /// A hash of the fields that represent the content of the story: its title, description and
+    /// the ID, last update and amount of words of each chapter.
+    ///
+    /// Metrics like views, comments or votes don't affect the result, and it is stable across
+    /// runs, so it can be stored to cheaply check if the content of a story changed.
+    pub fn content_fingerprint(&self) -> u64 {
+        let mut hasher = fingerprint::Fnv1a::new();
+        hasher.write_str(&self.title);
+        hasher.write_str(&self.description);
+        hasher.write_usize(self.chapters.len());
+        for chapter in &self.chapters {
+            hasher.write_u32(chapter.id);
+            hasher.write_i64(timestamp(&chapter.date_modified));
+            hasher.write_u64(chapter.words);
+        }
+        hasher.finish()
+    }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+    /// A hash of the content of the chapter with the given `id`: its title, last update and
+    /// amount of words.
+    ///
+    /// Like [`content_fingerprint()`](Story::content_fingerprint), views don't affect the result
+    /// and it is stable across runs, so it can be stored to only download again the chapters that
+    /// changed. Returns `None` if there isn't a chapter with that ID.
+    pub fn chapter_checksum(&self, id: Id) -> Option<u64> {
+        let chapter = self.chapter_by_id(id)?;
+        let mut hasher = fingerprint::Fnv1a::new();
+        hasher.write_str(&chapter.title);
+        hasher.write_i64(timestamp(&chapter.date_modified));
+        hasher.write_u64(chapter.words);
+        Some(hasher.finish())
+    }
 
-    use serde_json::Value;
+    /// A blurb for the story: the [`short_description`](Story::short_description) if not
+    /// empty, otherwise the [`description`](Story::description) truncated to `max_chars`
+    /// characters.
+    ///
+    /// The description is cut on a word boundary when possible, ending with an ellipsis that
+    /// counts towards `max_chars`.
+    pub fn summary(&self, max_chars: usize) -> String {
+        if !self.short_description.trim().is_empty() {
+            return self.short_description.clone();
+        }
 
-    static RESPONSE_SAMPLE: &str = r#"{
-  "story": {
-    "id": 428991,
-    "title": "How the Tantabus Parses Sleep",
-    "url": "https://www.fimfiction.net/story/428991/how-the-tantabus-parses-sleep",
-    "short_description": "The second Tantabus continues to grow, learn, and flourish. And maybe screw with certain ponies on the side.",
-    "description": "What started with laziness and a tiny slip-up in arcane intelligence creation has blossomed into its own being. With Luna's blessing, the second Tantabus is free to flit around the dreamscape as it pleases, unburdened by oversight. And what pleases it is making good dreams. Who couldn't be happy by making others happy?\r\n\r\nAs the Tantabus settles into its life, its horizons keep expanding, forever revealing more of the world and of ponies. There's always new things to learn. New dreams to spice up. New nightmares to beat down. New ponies to help. New ponies to annoy.\r\n\r\nDon't tell Luna about that last part.",
-    "date_modified": 1671122514,
-    "image": "https://cdn-img.fimfiction.net/story/iwqb-1673322192-428991-medium",
-    "full_image": "https://cdn-img.fimfiction.net/story/iwqb-1673322192-428991-full",
-    "views": 10712,
-    "total_views": 178800,
-    "words": 275949,
-    "chapter_count": 40,
-    "comments": 1929,
-    "author": {
-      "id": 253168,
-      "name": "Rambling Writer"
-    },
-    "status": "Incomplete",
-    "content_rating_text": "Everyone",
-    "content_rating": 0,
-    "likes": 1020,
-    "dislikes": 8,
-    "chapters": [
-      {
-        "id": 1273271,
-        "title": "Nightmares and the Deletion Thereof",
-        "words": 5215,
-        "views": 10712,
-        "link": "https://www.fimfiction.net/story/428991/1/how-the-tantabus-parses-sleep/nightmares-and-the-deletion-thereof",
-        "date_modified": 1631067292
-      },
-      {
-        "id": 1291292,
-        "title": "Robbery, He Dreamed",
-        "words": 4094,
-        "views": 7059,
-        "link": "https://www.fimfiction.net/story/428991/2/how-the-tantabus-parses-sleep/robbery-he-dreamed",
-        "date_modified": 1577915443
-      },
-      {
-        "id": 1306118,
-        "title": "Parallel Friendshipping",
-        "words": 6734,
-        "views": 6990,
-        "link": "https://www.fimfiction.net/story/428991/3/how-the-tantabus-parses-sleep/parallel-friendshipping",
-        "date_modified": 1557744585
-      },
-      {
-        "id": 1315597,
-        "title": "Tulpa ex Somnium",
-        "words": 5918,
-        "views": 6305,
-        "link": "https://www.fimfiction.net/story/428991/4/how-the-tantabus-parses-sleep/tulpa-ex-somnium",
-        "date_modified": 1560006968
-      },
-      {
-        "id": 1327818,
-        "title": "Halt and Catch Fire",
-        "words": 8041,
-        "views": 6796,
-        "link": "https://www.fimfiction.net/story/428991/5/how-the-tantabus-parses-sleep/halt-and-catch-fire",
-        "date_modified": 1563895815
-      },
-      {
-        "id": 1341288,
-        "title": "Corner Cases",
-        "words": 8133,
-        "views": 6279,
-        "link": "https://www.fimfiction.net/story/428991/6/how-the-tantabus-parses-sleep/corner-cases",
-        "date_modified": 1617627814
-      },
-      {
-        "id": 1349152,
-        "title": "Class Conversion",
-        "words": 5143,
-        "views": 6222,
-        "link": "https://www.fimfiction.net/story/428991/7/how-the-tantabus-parses-sleep/class-conversion",
-        "date_modified": 1597239916
-      },
-      {
-        "id": 1356526,
-        "title": "Frightening Foals for Fun and Finances",
-        "words": 6626,
-        "views": 5577,
-        "link": "https://www.fimfiction.net/story/428991/8/how-the-tantabus-parses-sleep/frightening-foals-for-fun-and-finances",
-        "date_modified": 1625766623
-      },
-      {
-        "id": 1365466,
-        "title": "Machine Teaching",
-        "words": 6451,
-        "views": 5855,
-        "link": "https://www.fimfiction.net/story/428991/9/how-the-tantabus-parses-sleep/machine-teaching",
-        "date_modified": 1592483985
-      },
-      {
-        "id": 1375472,
-        "title": "Source Incantations: Debugging",
-        "words": 5321,
-        "views": 5213,
-        "link": "https://www.fimfiction.net/story/428991/10/how-the-tantabus-parses-sleep/source-incantations-debugging",
-        "date_modified": 1598766861
-      },
-      {
-        "id": 1375809,
-        "title": "Source Incantations: Unit Testing",
-        "words": 6871,
-        "views": 5789,
-        "link": "https://www.fimfiction.net/story/428991/11/how-the-tantabus-parses-sleep/source-incantations-unit-testing",
-        "date_modified": 1578848620
+        let description = self.description.trim();
+        if description.chars().count() <= max_chars {
+            return description.to_string();
+        }
+        if max_chars == 0 {
+            return String::new();
+        }
+
+        let end = description
+            .char_indices()
+            .nth(max_chars - 1)
+            .map_or(description.len(), |(index, _)| index);
+        let cut = &description[..end];
+        let cut = match cut.rfind(char::is_whitespace) {
+            Some(index) if description[end..].starts_with(|c: char| !c.is_whitespace()) => {
+                &cut[..index]
+            }
+            _ => cut,
+        };
+
+        format!("{}…", cut.trim_end())
+    }
+
+    /// The first paragraph of the [`description`](Story::description): the text up to the
+    /// first `\r\n\r\n`, or the whole description if it has a single paragraph.
+    pub fn description_first_paragraph(&self) -> &str {
+        self.description
+            .split_once("\r\n\r\n")
+            .map_or(self.description.as_str(), |(first, _)| first)
+    }
+
+    /// Fraction of the stories in `corpus` that have less [`words`](Story::words) than this
+    /// one, between `0.0` and `1.0`.
+    ///
+    /// Stories with the same amount of words, including this one if it is in `corpus`, are not
+    /// counted as exceeded. Returns `0.0` for an empty `corpus`.
+    pub fn word_count_percentile(&self, corpus: &[Story]) -> f64 {
+        if corpus.is_empty() {
+            return 0.0;
+        }
+
+        let exceeded = corpus
+            .iter()
+            .filter(|story| story.words < self.words)
+            .count();
+        exceeded as f64 / corpus.len() as f64
+    }
+
+    /// Total amount of votes, likes plus dislikes, saturating at [`u32::MAX`].
+    ///
+    /// Returns `None` if votes are disabled.
+    pub fn vote_count(&self) -> Option<u32> {
+        Some(self.likes?.saturating_add(self.dislikes?))
+    }
+
+    /// Likes minus dislikes.
+    ///
+    /// Returns `None` if votes are disabled.
+    pub fn net_votes(&self) -> Option<i64> {
+        Some(i64::from(self.likes?) - i64::from(self.dislikes?))
+    }
+
+    /// Fraction of the votes that are likes, between `0.0` and `1.0`.
+    ///
+    /// Returns `None` if votes are disabled or there are no votes.
+    pub fn like_ratio(&self) -> Option<f64> {
+        match self.vote_count()? {
+            0 => None,
+            votes => Some(f64::from(self.likes?) / f64::from(votes)),
+        }
+    }
+
+    /// [`Story::like_ratio()`] rounded to `decimals` decimal places, which can be compared
+    /// exactly.
+    pub fn like_ratio_rounded(&self, decimals: u32) -> Option<f64> {
+        let ratio = self.like_ratio()?;
+        // Beyond this, rounding can't change an `f64` and the factor could overflow.
+        let factor = 10f64.powi(decimals.min(15) as i32);
+        Some((ratio * factor).round() / factor)
+    }
+
+    /// A score between `0` and `1` of how well received the story is, like to rank
+    /// recommendations. Returns `None` if votes are disabled.
+    ///
+    /// It is the mean of two parts, each between `0` and `1`:
+    /// * Approval: the lower bound of the Wilson score interval at 95% confidence (`z = 1.96`) of
+    ///   the fraction of likes among the `n = likes + dislikes` votes, so a few votes count for
+    ///   less than many with the same ratio. With `p = likes / n`, it is
+    ///   `(p + z²/2n - z·√(p(1 - p)/n + z²/4n²)) / (1 + z²/n)`, or `0` without votes.
+    /// * Interaction: the fraction of `likes + dislikes + comments` over
+    ///   [`total_views`](Story::total_views), capped at `1`, or `0` without views.
+    pub fn engagement_score(&self) -> Option<f64> {
+        const Z: f64 = 1.96;
+
+        let (likes, dislikes) = (f64::from(self.likes?), f64::from(self.dislikes?));
+        let votes = likes + dislikes;
+        let approval = if votes == 0.0 {
+            0.0
+        } else {
+            let p = likes / votes;
+            let z2 = Z * Z;
+            (p + z2 / (2.0 * votes)
+                - Z * (p * (1.0 - p) / votes + z2 / (4.0 * votes * votes)).sqrt())
+                / (1.0 + z2 / votes)
+        };
+
+        let interactions = votes + f64::from(self.comments);
+        let interaction = match self.total_views {
+            0 => 0.0,
+            views => (interactions / f64::from(views)).min(1.0),
+        };
+
+        Some((approval + interaction) / 2.0)
+    }
+
+    /// Whether both stories have the same content: title, descriptions and the
+    /// [content](Chapter::content_eq) of each chapter.
+    ///
+    /// Unlike a full comparison, metrics that change often like views, comments or votes are
+    /// ignored.
+    pub fn content_eq(&self, other: &Story) -> bool {
+        self.title == other.title
+            && self.short_description == other.short_description
+            && self.description == other.description
+            && self.chapters.len() == other.chapters.len()
+            && self
+                .chapters
+                .iter()
+                .zip(&other.chapters)
+                .all(|(chapter, other)| chapter.content_eq(other))
+    }
+
+    /// Fraction of [`chapter_count`](Story::chapter_count) present in
+    /// [`chapters`](Story::chapters).
+    ///
+    /// Returns `None` if either is empty.
+    pub fn completion_fraction(&self) -> Option<f64> {
+        if self.chapter_count == 0 || self.chapters.is_empty() {
+            return None;
+        }
+
+        Some(self.chapters.len() as f64 / self.chapter_count as f64)
+    }
+
+    /// Estimate of how many more chapters the story needs to reach `target_words`, assuming the
+    /// coming chapters are as long as the average of its [`chapters`](Story::chapters).
+    ///
+    /// Returns `Some(0)` if the story already has that many [`words`](Story::words), and `None`
+    /// if it is [`Complete`](StoryStatus::Complete), doesn't have any chapters or they don't
+    /// have any words.
+    pub fn estimated_chapters_remaining(&self, target_words: u64) -> Option<u64> {
+        if self.status == StoryStatus::Complete {
+            return None;
+        }
+
+        let (_, _, mean) = self.word_density_stats();
+        if mean <= 0.0 {
+            return None;
+        }
+
+        let remaining_words = target_words.saturating_sub(self.words);
+        Some((remaining_words as f64 / mean).ceil() as u64)
+    }
+
+    /// Average amount of [`comments`](Story::comments) per chapter, without any rounding.
+    ///
+    /// Returns `None` if [`chapter_count`](Story::chapter_count) is `0`.
+    pub fn comments_per_chapter(&self) -> Option<f64> {
+        if self.chapter_count == 0 {
+            return None;
+        }
+
+        Some(f64::from(self.comments) / self.chapter_count as f64)
+    }
+
+    /// Average amount of [`comments`](Story::comments) per day since the oldest chapter was
+    /// last updated, without any rounding.
+    ///
+    /// Returns `None` if the story doesn't have any chapters or the oldest chapter date isn't in
+    /// the past, like from clock skew.
+    #[cfg(feature = "chrono")]
+    pub fn comments_per_day(&self) -> Option<f64> {
+        self.comments_per_day_at(Utc::now())
+    }
+
+    #[cfg(feature = "chrono")]
+    fn comments_per_day_at(&self, now: DateTime<Utc>) -> Option<f64> {
+        let (oldest, _) = self.chapter_date_range_datetime()?;
+        let age_seconds = now.signed_duration_since(oldest).num_seconds();
+        if age_seconds <= 0 {
+            return None;
+        }
+
+        Some(f64::from(self.comments) / (age_seconds as f64 / 86400.0))
+    }
+
+    /// Name of the author of the story, or `"Unknown"` if it isn't known.
+    pub fn author_or_unknown(&self) -> &str {
+        match self.author.name.as_str() {
+            "" => "Unknown",
+            name => name,
+        }
+    }
+
+    /// The chapter with the given `id`, if the story has it.
+    pub fn chapter_by_id(&self, id: Id) -> Option<&Chapter> {
+        self.chapters.iter().find(|chapter| chapter.id == id)
+    }
+
+    /// A map of chapter IDs to their index in [`chapters`](Story::chapters), for repeated
+    /// lookups.
+    pub fn chapter_index(&self) -> HashMap<Id, usize> {
+        self.chapters
+            .iter()
+            .enumerate()
+            .map(|(index, chapter)| (chapter.id, index))
+            .collect()
+    }
+
+    /// [`date_modified`](Story::date_modified) in the timezone `tz`, like to group stories by
+    /// the calendar date of the reader.
+    #[cfg(feature = "chrono")]
+    pub fn modified_date_in<Tz: TimeZone>(&self, tz: &Tz) -> DateTime<Tz> {
+        self.date_modified.with_timezone(tz)
+    }
+
+    /// Whether the story was last updated more than `max_age` ago.
+    ///
+    /// A [`date_modified`](Story::date_modified) in the future, like from clock skew, is never
+    /// stale.
+    #[cfg(feature = "chrono")]
+    pub fn is_stale(&self, max_age: chrono::Duration) -> bool {
+        self.is_stale_at(Utc::now(), max_age)
+    }
+
+    #[cfg(feature = "chrono")]
+    fn is_stale_at(&self, now: DateTime<Utc>, max_age: chrono::Duration) -> bool {
+        now.signed_duration_since(self.date_modified) > max_age
+    }
+
+    /// Chapters last updated after the Unix timestamp `since`, in seconds, in reading order.
+    pub fn chapters_updated_after(&self, since: i64) -> Vec<&Chapter> {
+        self.chapters
+            .iter()
+            .filter(|chapter| timestamp(&chapter.date_modified) > since)
+            .collect()
+    }
+
+    /// Chapters last updated after `since`, in reading order.
+    #[cfg(feature = "chrono")]
+    pub fn chapters_updated_after_datetime(&self, since: DateTime<Utc>) -> Vec<&Chapter> {
+        self.chapters
+            .iter()
+            .filter(|chapter| chapter.date_modified > since)
+            .collect()
+    }
+
+    /// Oldest and newest [`date_modified`](Chapter::date_modified) of the chapters, as Unix
+    /// timestamps in seconds.
+    ///
+    /// Returns `None` if the story doesn't have any chapters.
+    pub fn chapter_date_range(&self) -> Option<(i64, i64)> {
+        let (oldest, newest) = self.chapter_date_range_dates()?;
+        Some((timestamp(oldest), timestamp(newest)))
+    }
+
+    /// Oldest and newest [`date_modified`](Chapter::date_modified) of the chapters.
+    ///
+    /// Returns `None` if the story doesn't have any chapters.
+    #[cfg(feature = "chrono")]
+    pub fn chapter_date_range_datetime(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let (oldest, newest) = self.chapter_date_range_dates()?;
+        Some((*oldest, *newest))
+    }
+
+    fn chapter_date_range_dates(&self) -> Option<(&Date, &Date)> {
+        let mut dates = self.chapters.iter().map(|chapter| &chapter.date_modified);
+        let first = dates.next()?;
+        Some(dates.fold((first, first), |(oldest, newest), date| {
+            (oldest.min(date), newest.max(date))
+        }))
+    }
+
+    /// Time between the updates of consecutive chapters, with the chapters sorted by their
+    /// [`date_modified`](Chapter::date_modified).
+    ///
+    /// Returns an empty list for stories with less than 2 chapters.
+    #[cfg(feature = "chrono")]
+    pub fn update_gaps(&self) -> Vec<chrono::Duration> {
+        let mut dates: Vec<DateTime<Utc>> = self
+            .chapters
+            .iter()
+            .map(|chapter| chapter.date_modified)
+            .collect();
+        dates.sort_unstable();
+        dates
+            .windows(2)
+            .map(|pair| pair[1].signed_duration_since(pair[0]))
+            .collect()
+    }
+
+    /// Longest of the [`update_gaps()`](Story::update_gaps), like to spot a hiatus.
+    ///
+    /// Returns `None` for stories with less than 2 chapters.
+    #[cfg(feature = "chrono")]
+    pub fn longest_update_gap(&self) -> Option<chrono::Duration> {
+        self.update_gaps().into_iter().max()
+    }
+
+    /// [`link`](Chapter::link) of each chapter, in reading order.
+    pub fn chapter_links(&self) -> impl Iterator<Item = &str> {
+        self.chapters.iter().map(|chapter| chapter.link.as_str())
+    }
+
+    /// [`id`](Chapter::id) of each chapter, in reading order.
+    pub fn chapter_ids(&self) -> impl Iterator<Item = Id> + '_ {
+        self.chapters.iter().map(|chapter| chapter.id)
+    }
+
+    /// Whether more than one chapter has the same [`id`](Chapter::id), which only a malformed
+    /// response would have.
+    pub fn has_duplicate_chapters(&self) -> bool {
+        let mut ids = HashSet::with_capacity(self.chapters.len());
+        !self.chapter_ids().all(|id| ids.insert(id))
+    }
+
+    /// Removes the chapters with the same [`id`](Chapter::id) as an earlier one, keeping the
+    /// first occurrence.
+    ///
+    /// [`chapter_count`](Story::chapter_count) and [`words`](Story::words) are kept as is, see
+    /// [`recompute_totals()`](Story::recompute_totals) to update them.
+    pub fn dedupe_chapters(&mut self) {
+        let mut ids = HashSet::with_capacity(self.chapters.len());
+        self.chapters.retain(|chapter| ids.insert(chapter.id));
+    }
+
+    /// Amount of words of each chapter, in reading order.
+    pub fn chapter_word_counts(&self) -> Vec<u64> {
+        self.chapters.iter().map(|chapter| chapter.words).collect()
+    }
+
+    /// Minimum, maximum and mean amount of words of the chapters of the story.
+    ///
+    /// All of them are `0` if the story doesn't have any chapters.
+    pub fn word_density_stats(&self) -> (u64, u64, f64) {
+        let words = self.chapters.iter().map(|chapter| chapter.words);
+        let (Some(min), Some(max)) = (words.clone().min(), words.clone().max()) else {
+            return (0, 0, 0.0);
+        };
+
+        let mean = words.sum::<u64>() as f64 / self.chapters.len() as f64;
+        (min, max, mean)
+    }
+
+    /// Chapters whose amount of words is more than `factor` standard deviations away from the
+    /// mean of the story, in reading order.
+    ///
+    /// Returns an empty list for stories with less than 3 chapters, where the deviation isn't
+    /// meaningful.
+    pub fn pacing_outliers(&self, factor: f64) -> Vec<&Chapter> {
+        if self.chapters.len() < 3 {
+            return Vec::new();
+        }
+
+        let (_, _, mean) = self.word_density_stats();
+        let variance = self
+            .chapters
+            .iter()
+            .map(|chapter| (chapter.words as f64 - mean).powi(2))
+            .sum::<f64>()
+            / self.chapters.len() as f64;
+        let max_deviation = factor * variance.sqrt();
+
+        self.chapters
+            .iter()
+            .filter(|chapter| (chapter.words as f64 - mean).abs() > max_deviation)
+            .collect()
+    }
+
+    /// [`tags`](Story::tags) of the given `kind`.
+    pub fn tags_of_kind(&self, kind: TagKind) -> impl Iterator<Item = &Tag> {
+        self.tags.iter().filter(move |tag| tag.kind == kind)
+    }
+
+    /// Splits the story into its metadata and its chapters, leaving
+    /// [`chapters`](Story::chapters) of the former empty.
+    ///
+    /// [`chapter_count`](Story::chapter_count) and [`words`](Story::words) are kept as is.
+    pub fn into_parts(mut self) -> (Story, Vec<Chapter>) {
+        let chapters = std::mem::take(&mut self.chapters);
+        (self, chapters.into_vec())
+    }
+
+    /// Appends the chapters of another page of the story, like the ones removed by
+    /// [`take_chapters()`](Story::take_chapters), skipping those with the
+    /// [`id`](Chapter::id) of one it already has.
+    ///
+    /// [`chapter_count`](Story::chapter_count) is raised to the amount of chapters if it was
+    /// lower, so the total given by the API is kept while not all pages are appended.
+    pub fn append_chapters(&mut self, more: Vec<Chapter>) {
+        let mut ids: HashSet<Id> = self.chapter_ids().collect();
+        self.chapters
+            .extend(more.into_iter().filter(|chapter| ids.insert(chapter.id)));
+        self.chapter_count = self.chapter_count.max(self.chapters.len() as u64);
+    }
+
+    /// Keeps only the first `n` chapters, like for a preview.
+    ///
+    /// [`chapter_count`](Story::chapter_count) and [`words`](Story::words) are kept as is, so
+    /// it is still known that there are more chapters.
+    pub fn truncate_chapters(&mut self, n: usize) {
+        self.chapters.truncate(n);
+    }
+
+    /// Removes the first `n` chapters and returns them, like to send a story a page at a time.
+    ///
+    /// [`chapter_count`](Story::chapter_count) and [`words`](Story::words) are kept as is.
+    pub fn take_chapters(&mut self, n: usize) -> Vec<Chapter> {
+        let mut taken = std::mem::take(&mut self.chapters).into_vec();
+        self.chapters = taken.split_off(n.min(taken.len())).into();
+        taken
+    }
+
+    /// Estimate of the memory taken by the story, the size of the struct plus the length of its
+    /// strings and the [estimated size](Chapter::approximate_size_bytes) of its chapters.
+    ///
+    /// It isn't exact, as it ignores any spare capacity, but it is consistent between stories so
+    /// it can be used to keep a cache within a memory budget.
+    pub fn approximate_size_bytes(&self) -> usize {
+        let strings = [
+            Some(&self.title),
+            Some(&self.url),
+            Some(&self.short_description),
+            Some(&self.description),
+            self.image.as_ref(),
+            self.full_image.as_ref(),
+            self.language.as_ref(),
+            Some(&self.author.name),
+        ];
+        let tags: usize = self
+            .tags
+            .iter()
+            .map(|tag| std::mem::size_of::<Tag>() + tag.name.len())
+            .sum();
+        let groups: usize = self
+            .groups
+            .iter()
+            .map(|group| std::mem::size_of::<GroupLink>() + group.name.len() + group.url.len())
+            .sum();
+        let chapters: usize = self
+            .chapters
+            .iter()
+            .map(Chapter::approximate_size_bytes)
+            .sum();
+
+        std::mem::size_of::<Story>()
+            + strings
+                .into_iter()
+                .flatten()
+                .map(String::len)
+                .sum::<usize>()
+            + tags
+            + groups
+            + chapters
+    }
+
+    /// How many [`words`](Story::words) the story gained since the `previous` snapshot of it,
+    /// negative if it lost words.
+    pub fn words_added_since(&self, previous: &Story) -> i64 {
+        i64::try_from(self.words).unwrap_or(i64::MAX)
+            - i64::try_from(previous.words).unwrap_or(i64::MAX)
+    }
+
+    /// How many chapters, by [`chapter_count`](Story::chapter_count), the story gained since the
+    /// `previous` snapshot of it, negative if it lost chapters.
+    pub fn chapters_added_since(&self, previous: &Story) -> i64 {
+        i64::try_from(self.chapter_count).unwrap_or(i64::MAX)
+            - i64::try_from(previous.chapter_count).unwrap_or(i64::MAX)
+    }
+
+    /// The [`full_image`](Story::full_image), or the one derived from the
+    /// [`image`](Story::image) thumbnail if missing.
+    ///
+    /// The CDN gives both sizes at the same URL save for a `-medium` or `-full` suffix, so the
+    /// full size one can only be derived from a thumbnail URL with that suffix.
+    pub fn full_image_or_derived(&self) -> Option<String> {
+        self.full_image
+            .clone()
+            .or_else(|| derive_full_image(self.image.as_deref()?))
+    }
+
+    /// Rewrites the `http://` URLs on Fimfiction and its image CDN into `https://`: the
+    /// [`url`](Story::url), cover images, group URLs and chapter links.
+    ///
+    /// URLs on other hosts are left untouched.
+    pub fn normalize_urls_https(&mut self) {
+        fn upgrade(url: &mut String) {
+            if url.starts_with("http://") && validate::is_fimfiction_url(url) {
+                url.replace_range(.."http".len(), "https");
+            }
+        }
+
+        upgrade(&mut self.url);
+        self.image.iter_mut().for_each(upgrade);
+        self.full_image.iter_mut().for_each(upgrade);
+        for group in &mut self.groups {
+            upgrade(&mut group.url);
+        }
+        for chapter in &mut self.chapters {
+            upgrade(&mut chapter.link);
+        }
+    }
+
+    /// Whether the story is a oneshot, a [`Complete`](StoryStatus::Complete) story with a single
+    /// chapter.
+    pub fn is_oneshot(&self) -> bool {
+        self.chapter_count == 1 && self.status == StoryStatus::Complete
+    }
+
+    /// Sets the [`content_rating`](Story::content_rating) of the story, same as assigning the
+    /// field.
+    ///
+    /// The rating is serialized as both the `content_rating` number and the
+    /// `content_rating_text`.
+    pub fn set_rating(&mut self, rating: StoryRating) {
+        self.content_rating = rating;
+    }
+
+    /// Whether the story is visible to everyone.
+    ///
+    /// Stories without a [`publication_state`](Story::publication_state) are taken as published,
+    /// as the API only gives published stories.
+    pub fn is_published(&self) -> bool {
+        matches!(
+            self.publication_state,
+            None | Some(PublicationState::Published)
+        )
+    }
+
+    /// Whether the story is written in English.
+    ///
+    /// Stories without a [`language`](Story::language) are taken as English, the default
+    /// language of Fimfiction.
+    pub fn is_english(&self) -> bool {
+        self.language.as_deref().is_none_or(|language| {
+            language.eq_ignore_ascii_case("english")
+                || language.eq_ignore_ascii_case("en")
+                || language
+                    .get(..3)
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case("en-"))
+        })
+    }
+
+    /// Recalculates [`chapter_count`](Story::chapter_count) and [`words`](Story::words) from
+    /// [`chapters`](Story::chapters).
+    ///
+    /// Useful to keep a story consistent after adding or removing chapters by hand.
+    pub fn recompute_totals(&mut self) {
+        self.chapter_count = self.chapters.len() as u64;
+        self.words = self.chapters.iter().map(|chapter| chapter.words).sum();
+    }
+}
+
+/// Represents errors that can occur while deserializing a [`Story`].
+#[derive(Debug, Error)]
+pub enum StoryError {
+    /// A deserialization error.
+    #[error("json deserialization error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The story is not public, content is incomplete.
+    ///
+    /// The story is boxed to keep the error, and every `Result` with it, small.
+    #[error("unpublised error: incomplete content")]
+    Unpublished(Box<Story>),
+
+    /// Alias for `"Invalid story id"` API error message.
+    #[error("API error: Invalid story ID")]
+    InvalidId,
+
+    /// An API error message which doesn't have its own variant, with the code given next to it
+    /// if any.
+    #[error("API error: {message}")]
+    Api {
+        /// Message of the error.
+        message: String,
+        /// Numeric code of the error, if given.
+        code: Option<i32>,
+    },
+
+    /// A valid JSON response that isn't a story nor an error, see [`Response::Unknown`].
+    #[error("unexpected response shape")]
+    UnexpectedShape(Value),
+}
+
+/// Converts into an [`io::Error`](std::io::Error) of kind
+/// [`InvalidData`](std::io::ErrorKind::InvalidData), with the [`StoryError`] as its source, so `?`
+/// can be used in functions returning an [`io::Result`](std::io::Result).
+impl From<StoryError> for std::io::Error {
+    fn from(err: StoryError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+impl StoryError {
+    /// The error for an API error message and its code.
+    fn from_api_message(message: String, code: Option<i32>) -> StoryError {
+        match message.as_str() {
+            "Invalid story id" => StoryError::InvalidId,
+            _ => {
+                #[cfg(feature = "log")]
+                log::debug!("unknown API error message: {message:?}");
+                StoryError::Api { message, code }
+            }
+        }
+    }
+
+    /// Whether retrying the request that caused this error could succeed.
+    ///
+    /// Only [`Api`](StoryError::Api) errors are taken as transient, unless their code is a client
+    /// error in the `4xx` range other than rate limiting (`429`). Every other error would be given
+    /// again for the same story.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            StoryError::Api { code, .. } => {
+                !matches!(code, Some(code) if (400..500).contains(code) && *code != 429)
+            }
+            StoryError::Json(_)
+            | StoryError::Unpublished(_)
+            | StoryError::InvalidId
+            | StoryError::UnexpectedShape(_) => false,
+        }
+    }
+}
+
+/// Represents the different responses that the Fimfiction story API can return.
+// Almost every response is a story, which is moved out right away.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum Response {
+    /// The API returned a [`Story`].
+    Story(Story),
+    /// The API returned an error.
+    ///
+    /// Some errors give a numeric `code` next to the `error` message.
+    Error {
+        /// Message of the error.
+        message: String,
+        /// Numeric code of the error, if given.
+        code: Option<i32>,
+    },
+    /// The API returned a response that isn't recognized, kept as is for inspection.
+    ///
+    /// Only a top-level JSON value that isn't an object with a single `story` key or an `error`
+    /// key with an optional `code` ends up here, a malformed story or error is still a
+    /// deserialization error.
+    Unknown(Value),
+}
+
+impl Response {
+    /// Converts the response into the [`Story`] it contains.
+    ///
+    /// # Errors
+    /// * The response is of the [`Error`](Response::Error) variant.
+    /// * The response is of the [`Unknown`](Response::Unknown) variant.
+    /// * The [`Story`] does not have any chapters.
+    pub fn into_result(self) -> Result<Story, StoryError> {
+        match self {
+            Response::Story(story) => {
+                if story.chapters.is_empty() && story.chapter_count == 0 {
+                    Err(StoryError::Unpublished(Box::new(story)))
+                } else {
+                    Ok(story)
+                }
+            }
+            Response::Error { message, code } => Err(StoryError::from_api_message(message, code)),
+            Response::Unknown(value) => Err(StoryError::UnexpectedShape(value)),
+        }
+    }
+}
+
+impl From<Story> for Response {
+    fn from(story: Story) -> Self {
+        Response::Story(story)
+    }
+}
+
+/// Every key of a story object that is deserialized, including aliases.
+///
+/// Checked against the keys [`repr::StoryRepr`] accepts by a test, so it has to be updated along
+/// with it.
+#[cfg(feature = "log")]
+const KNOWN_STORY_FIELDS: &[&str] = &[
+    "id",
+    "title",
+    "url",
+    "short_description",
+    "shortDescription",
+    "description",
+    "date_modified",
+    "dateModified",
+    "image",
+    "full_image",
+    "fullImage",
+    "views",
+    "num_views",
+    "total_views",
+    "total_num_views",
+    "totalViews",
+    "words",
+    "chapter_count",
+    "num_chapters",
+    "number_of_chapters",
+    "chapterCount",
+    "comments",
+    "num_comments",
+    "author",
+    "status",
+    "publication_state",
+    "language",
+    "content_rating_text",
+    "contentRatingText",
+    "content_rating",
+    "contentRating",
+    "rating",
+    "likes",
+    "num_likes",
+    "dislikes",
+    "num_dislikes",
+    "tags",
+    "groups",
+    "chapters",
+];
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+    #[serde(default)]
+    code: Option<i32>,
+}
+
+impl Serialize for Response {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Response::Story(story) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("story", story)?;
+                map.end()
+            }
+            Response::Error { message, code } => {
+                let mut map = serializer.serialize_map(Some(1 + usize::from(code.is_some())))?;
+                map.serialize_entry("error", message)?;
+                if let Some(code) = code {
+                    map.serialize_entry("code", code)?;
+                }
+                map.end()
+            }
+            Response::Unknown(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Response {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ResponseVisitor)
+    }
+}
+
+/// Deserializes a [`Response`] by the first key of the top-level object, so that a story is
+/// deserialized as it is read, with the position of any error in it, and anything else is
+/// buffered into a [`Value`] to tell an error response from an [`Unknown`](Response::Unknown)
+/// one.
+struct ResponseVisitor;
+
+impl<'de> de::Visitor<'de> for ResponseVisitor {
+    type Value = Response;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a story API response")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E> {
+        Ok(Response::Unknown(Value::from(value)))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+        Ok(Response::Unknown(Value::from(value)))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(Response::Unknown(Value::from(value)))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E> {
+        Ok(Response::Unknown(Value::from(value)))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(Response::Unknown(Value::from(value)))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Response::Unknown(Value::Null))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        Value::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(Response::Unknown)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut object = serde_json::Map::new();
+        match map.next_key::<String>()? {
+            Some(key) if key == "story" => {
+                let story = map.next_value_seed(StorySeed)?;
+                match map.next_key::<String>()? {
+                    None => return Ok(Response::Story(story)),
+                    // Keys next to a story make it unknown, keeping the story as deserialized.
+                    Some(key) => {
+                        let story = serde_json::to_value(story).map_err(de::Error::custom)?;
+                        object.insert("story".to_string(), story);
+                        object.insert(key, map.next_value()?);
+                    }
+                }
+            }
+            Some(key) => {
+                object.insert(key, map.next_value()?);
+            }
+            None => {}
+        }
+        while let Some((key, value)) = map.next_entry()? {
+            object.insert(key, value);
+        }
+
+        let is_error = object.contains_key("error")
+            && object.keys().all(|key| key == "error" || key == "code");
+        if !is_error {
+            return Ok(Response::Unknown(Value::Object(object)));
+        }
+
+        let ErrorResponse { error, code } =
+            ErrorResponse::deserialize(Value::Object(object)).map_err(de::Error::custom)?;
+        Ok(Response::Error {
+            message: error,
+            code,
+        })
+    }
+}
+
+/// Deserializes the [`Story`] of a response, warning about the keys it doesn't know with the
+/// `log` feature.
+struct StorySeed;
+
+impl<'de> de::DeserializeSeed<'de> for StorySeed {
+    type Value = Story;
+
+    #[cfg(not(feature = "log"))]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Story::deserialize(deserializer)
+    }
+
+    #[cfg(feature = "log")]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+#[cfg(feature = "log")]
+impl<'de> de::Visitor<'de> for StorySeed {
+    type Value = Story;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a story object")
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        Story::deserialize(de::value::MapAccessDeserializer::new(UnknownFieldsWarning(
+            map,
+        )))
+    }
+}
+
+/// [`MapAccess`](de::MapAccess) of a story object that warns about every key not in
+/// [`KNOWN_STORY_FIELDS`].
+#[cfg(feature = "log")]
+struct UnknownFieldsWarning<A>(A);
+
+#[cfg(feature = "log")]
+impl<'de, A> de::MapAccess<'de> for UnknownFieldsWarning<A>
+where
+    A: de::MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        use de::IntoDeserializer;
+
+        let Some(key) = self.0.next_key::<String>()? else {
+            return Ok(None);
+        };
+        if !KNOWN_STORY_FIELDS.contains(&key.as_str()) {
+            log::warn!("unknown story field `{key}`");
+        }
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.0.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+/// Deserialize an instance of [`Response`] from an API response String.
+///
+/// Unlike [`from_str()`], API errors and unpublished stories are not turned into a
+/// [`StoryError`], leaving it to the caller.
+pub fn parse_response(input: &str) -> Result<Response, serde_json::Error> {
+    serde_json::from_str(input)
+}
+
+/// Deserialize an instance of [`Story`] from an API response String.
+///
+/// # Errors
+/// * On a deserialization error (see [`serde_json::from_str()`]).
+/// * On any of the errors of [`Response::into_result()`].
+pub fn from_str(input: &str) -> Result<Story, StoryError> {
+    parse_response(input)?.into_result()
+}
+
+/// Deserialize an instance of [`Story`] from an API response String that may be nested under a
+/// `data` key, like `{"data": {"story": ...}}`.
+///
+/// Responses without the `data` envelope are taken as is, like in [`from_str()`].
+///
+/// # Errors
+/// Same as [`from_str()`].
+pub fn from_str_nested(input: &str) -> Result<Story, StoryError> {
+    let mut value: Value = serde_json::from_str(input)?;
+    if let Some(data) = value
+        .as_object_mut()
+        .filter(|object| object.len() == 1)
+        .and_then(|object| object.remove("data"))
+    {
+        value = data;
+    }
+    Response::deserialize(value)?.into_result()
+}
+
+/// Parses a [`Story::short_id()`] back into the ID, in any casing.
+///
+/// Returns `None` if `short_id` is empty, isn't in base 36 or is too large for an [`Id`].
+pub fn parse_short_id(short_id: &str) -> Option<Id> {
+    if short_id.is_empty() || !short_id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Id::from_str_radix(short_id, 36).ok()
+}
+
+/// Collect `stories` into a map keyed by their ID.
+///
+/// On duplicate IDs the story with the newest [`date_modified`](Story::date_modified) is kept, or
+/// the first one if they were updated at the same time.
+pub fn collect_by_id(stories: impl IntoIterator<Item = Story>) -> HashMap<Id, Story> {
+    let mut map: HashMap<Id, Story> = HashMap::new();
+    for story in stories {
+        match map.get(&story.id) {
+            Some(kept) if kept.date_modified >= story.date_modified => {}
+            _ => {
+                map.insert(story.id, story);
+            }
+        }
+    }
+    map
+}
+
+/// Count how many `stories` fall in each bucket of [`words`](Story::words), keyed by the
+/// smallest word count of the bucket.
+///
+/// A story with `words` falls in the bucket `words - words % bucket_size`, so with a
+/// `bucket_size` of `1000` the bucket `2000` holds the stories with 2000 to 2999 words. Empty
+/// buckets are left out and a `bucket_size` of `0` is taken as `1`.
+pub fn word_count_histogram(stories: &[Story], bucket_size: u64) -> BTreeMap<u64, usize> {
+    let bucket_size = bucket_size.max(1);
+    let mut histogram = BTreeMap::new();
+    for story in stories {
+        *histogram
+            .entry(story.words - story.words % bucket_size)
+            .or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Serialize a [`Story`] as a Fimfiction story response String.
+///
+/// A convenience function for wrapping `story` into a [`Response`] and getting the string from
+/// [`serde_json::to_string()`].
+pub fn to_string(story: Story) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&Response::from(story))
+}
+
+/// Serialize a [`Story`] as a Fimfiction story response String, leaving out optional fields that
+/// are `None` instead of serializing them as `null`.
+///
+/// Matches stripped responses more closely than [`to_string()`], which should be preferred for
+/// full fidelity. Keys are in the same order as in [`to_string()`].
+pub fn to_string_compact(story: Story) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&HashMap::from([("story", repr::StoryRepr::compact(story))]))
+}
+
+/// Asserts that the story response `input` is serialized back into the same JSON after being
+/// deserialized, to check that the crate handles real-world responses without losing data.
+///
+/// The comparison is between JSON values, so whitespace and key order don't matter. But keys
+/// given by an alias or missing optional fields are serialized differently, so `input` should use
+/// the same keys as the API. With the `derive-full-image` feature, `input` should also give the
+/// `full_image` of stories with an [`image`](Story::image).
+///
+/// # Panics
+/// If `input` isn't a story response or it changes on the round trip.
+#[cfg(any(test, feature = "test-util"))]
+pub fn assert_roundtrip(input: &str) {
+    let value: Value = serde_json::from_str(input).expect("input should be valid JSON");
+    let story =
+        from_str(input).unwrap_or_else(|err| panic!("input should be a story response: {err}"));
+    let serialized_value =
+        serde_json::to_value(Response::from(story)).expect("story should be serializable");
+
+    assert_eq!(
+        value, serialized_value,
+        "story response should be the same after a round trip"
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use serde_json::Map;
+
+    static RESPONSE_SAMPLE: &str = r#"{
+  "story": {
+    "id": 428991,
+    "title": "How the Tantabus Parses Sleep",
+    "url": "https://www.fimfiction.net/story/428991/how-the-tantabus-parses-sleep",
+    "short_description": "The second Tantabus continues to grow, learn, and flourish. And maybe screw with certain ponies on the side.",
+    "description": "What started with laziness and a tiny slip-up in arcane intelligence creation has blossomed into its own being. With Luna's blessing, the second Tantabus is free to flit around the dreamscape as it pleases, unburdened by oversight. And what pleases it is making good dreams. Who couldn't be happy by making others happy?\r\n\r\nAs the Tantabus settles into its life, its horizons keep expanding, forever revealing more of the world and of ponies. There's always new things to learn. New dreams to spice up. New nightmares to beat down. New ponies to help. New ponies to annoy.\r\n\r\nDon't tell Luna about that last part.",
+    "date_modified": 1671122514,
+    "image": "https://cdn-img.fimfiction.net/story/iwqb-1673322192-428991-medium",
+    "full_image": "https://cdn-img.fimfiction.net/story/iwqb-1673322192-428991-full",
+    "views": 10712,
+    "total_views": 178800,
+    "words": 275949,
+    "chapter_count": 40,
+    "comments": 1929,
+    "author": {
+      "id": 253168,
+      "name": "Rambling Writer"
+    },
+    "status": "Incomplete",
+    "content_rating_text": "Everyone",
+    "content_rating": 0,
+    "likes": 1020,
+    "dislikes": 8,
+    "chapters": [
+      {
+        "id": 1273271,
+        "title": "Nightmares and the Deletion Thereof",
+        "words": 5215,
+        "views": 10712,
+        "link": "https://www.fimfiction.net/story/428991/1/how-the-tantabus-parses-sleep/nightmares-and-the-deletion-thereof",
+        "date_modified": 1631067292
+      },
+      {
+        "id": 1291292,
+        "title": "Robbery, He Dreamed",
+        "words": 4094,
+        "views": 7059,
+        "link": "https://www.fimfiction.net/story/428991/2/how-the-tantabus-parses-sleep/robbery-he-dreamed",
+        "date_modified": 1577915443
+      },
+      {
+        "id": 1306118,
+        "title": "Parallel Friendshipping",
+        "words": 6734,
+        "views": 6990,
+        "link": "https://www.fimfiction.net/story/428991/3/how-the-tantabus-parses-sleep/parallel-friendshipping",
+        "date_modified": 1557744585
+      },
+      {
+        "id": 1315597,
+        "title": "Tulpa ex Somnium",
+        "words": 5918,
+        "views": 6305,
+        "link": "https://www.fimfiction.net/story/428991/4/how-the-tantabus-parses-sleep/tulpa-ex-somnium",
+        "date_modified": 1560006968
+      },
+      {
+        "id": 1327818,
+        "title": "Halt and Catch Fire",
+        "words": 8041,
+        "views": 6796,
+        "link": "https://www.fimfiction.net/story/428991/5/how-the-tantabus-parses-sleep/halt-and-catch-fire",
+        "date_modified": 1563895815
+      },
+      {
+        "id": 1341288,
+        "title": "Corner Cases",
+        "words": 8133,
+        "views": 6279,
+        "link": "https://www.fimfiction.net/story/428991/6/how-the-tantabus-parses-sleep/corner-cases",
+        "date_modified": 1617627814
+      },
+      {
+        "id": 1349152,
+        "title": "Class Conversion",
+        "words": 5143,
+        "views": 6222,
+        "link": "https://www.fimfiction.net/story/428991/7/how-the-tantabus-parses-sleep/class-conversion",
+        "date_modified": 1597239916
+      },
+      {
+        "id": 1356526,
+        "title": "Frightening Foals for Fun and Finances",
+        "words": 6626,
+        "views": 5577,
+        "link": "https://www.fimfiction.net/story/428991/8/how-the-tantabus-parses-sleep/frightening-foals-for-fun-and-finances",
+        "date_modified": 1625766623
+      },
+      {
+        "id": 1365466,
+        "title": "Machine Teaching",
+        "words": 6451,
+        "views": 5855,
+        "link": "https://www.fimfiction.net/story/428991/9/how-the-tantabus-parses-sleep/machine-teaching",
+        "date_modified": 1592483985
+      },
+      {
+        "id": 1375472,
+        "title": "Source Incantations: Debugging",
+        "words": 5321,
+        "views": 5213,
+        "link": "https://www.fimfiction.net/story/428991/10/how-the-tantabus-parses-sleep/source-incantations-debugging",
+        "date_modified": 1598766861
+      },
+      {
+        "id": 1375809,
+        "title": "Source Incantations: Unit Testing",
+        "words": 6871,
+        "views": 5789,
+        "link": "https://www.fimfiction.net/story/428991/11/how-the-tantabus-parses-sleep/source-incantations-unit-testing",
+        "date_modified": 1578848620
       },
       {
         "id": 1399983,
@@ -544,92 +1968,1948 @@ mod test {
   }
 }"#;
 
+    /// Returns [`RESPONSE_SAMPLE`] with `edit` applied to its story object.
+    fn edited_sample(edit: impl FnOnce(&mut Map<String, Value>)) -> String {
+        let mut value: Value = serde_json::from_str(RESPONSE_SAMPLE).unwrap();
+        edit(value["story"].as_object_mut().unwrap());
+        value.to_string()
+    }
+
+    /// Returns [`RESPONSE_SAMPLE`] with the `from` key of its story object renamed to `to`.
+    fn renamed_sample(from: &str, to: &str) -> String {
+        edited_sample(|story| {
+            let value = story.remove(from).unwrap();
+            story.insert(to.to_string(), value);
+        })
+    }
+
+    /// Every alias of a story field, next to the field it stands for.
+    const STORY_FIELD_ALIASES: &[(&str, &str)] = &[
+        ("short_description", "shortDescription"),
+        ("date_modified", "dateModified"),
+        ("full_image", "fullImage"),
+        ("views", "num_views"),
+        ("total_views", "total_num_views"),
+        ("total_views", "totalViews"),
+        ("chapter_count", "num_chapters"),
+        ("chapter_count", "number_of_chapters"),
+        ("chapter_count", "chapterCount"),
+        ("comments", "num_comments"),
+        ("content_rating_text", "contentRatingText"),
+        ("content_rating", "contentRating"),
+        ("likes", "num_likes"),
+        ("dislikes", "num_dislikes"),
+    ];
+
+    /// Returns every key `T` accepts when deserialized from a map, aliases included, as serde
+    /// hands them to [`Deserializer::deserialize_struct()`].
+    fn accepted_fields<'de, T: Deserialize<'de>>() -> Vec<&'static str> {
+        use de::value::Error;
+        use de::{Error as _, Visitor};
+
+        struct Probe<'a>(&'a mut &'static [&'static str]);
+
+        impl<'de> Deserializer<'de> for Probe<'_> {
+            type Error = Error;
+
+            fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+                Err(Error::custom("expected a struct"))
+            }
+
+            fn deserialize_struct<V: Visitor<'de>>(
+                self,
+                _: &'static str,
+                fields: &'static [&'static str],
+                _: V,
+            ) -> Result<V::Value, Error> {
+                *self.0 = fields;
+                Err(Error::custom("probed"))
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+                byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map enum
+                identifier ignored_any
+            }
+        }
+
+        let mut fields: &[&str] = &[];
+        assert!(T::deserialize(Probe(&mut fields)).is_err());
+        fields.to_vec()
+    }
+
+    #[test]
+    fn deserialize_story_response() {
+        from_str(RESPONSE_SAMPLE).expect("response should be deserialized into a Story");
+    }
+
+    #[test]
+    fn story_field_aliases() {
+        let fields = accepted_fields::<Story>();
+        let sample: Value = serde_json::from_str(RESPONSE_SAMPLE).unwrap();
+        let sample_keys = sample["story"].as_object().unwrap();
+        for field in &fields {
+            assert!(
+                sample_keys.contains_key(*field)
+                    || STORY_FIELD_ALIASES.iter().any(|(_, alias)| alias == field)
+                    || ["publication_state", "language", "rating", "tags", "groups"]
+                        .contains(field),
+                "`{field}` is neither in the sample nor a known alias"
+            );
+        }
+
+        #[cfg(feature = "cow")]
+        assert_eq!(accepted_fields::<cow::StoryCow>(), fields);
+
+        let expected = serde_json::to_value(from_str(RESPONSE_SAMPLE).unwrap()).unwrap();
+        for (field, alias) in STORY_FIELD_ALIASES {
+            assert!(fields.contains(alias), "`{alias}` is not accepted");
+
+            let renamed = renamed_sample(field, alias);
+            let story = from_str(&renamed).unwrap();
+            assert_eq!(serde_json::to_value(&story).unwrap(), expected, "{alias}");
+
+            #[cfg(feature = "cow")]
+            {
+                let borrowed = cow::from_str_borrowed(&renamed).unwrap();
+                assert!(borrowed == story, "{alias}");
+                assert_eq!(
+                    serde_json::to_value(&borrowed).unwrap(),
+                    expected,
+                    "{alias}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_story_response() {
+        let story =
+            from_str(RESPONSE_SAMPLE).expect("response should be deserialized into a Story");
+        to_string(story).expect("Story should be serializable as a String");
+    }
+
+    #[test]
+    fn serde_reversible() {
+        assert_roundtrip(RESPONSE_SAMPLE);
+    }
+
+    #[test]
+    #[should_panic(expected = "story response should be the same after a round trip")]
+    fn assert_roundtrip_changed() {
+        assert_roundtrip(&renamed_sample("views", "num_views"));
+    }
+
+    #[test]
+    fn estimated_pages() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.estimated_pages(1000), 276);
+        assert_eq!(story.estimated_pages_default(), 1104);
+        assert_eq!(story.estimated_pages(0), 0);
+
+        let chapter = &story.chapters[0];
+        assert_eq!(chapter.estimated_pages(5215), 1);
+        assert_eq!(chapter.estimated_pages_default(), 21);
+        assert_eq!(chapter.estimated_pages(0), 0);
+    }
+
+    #[test]
+    fn content_fingerprint() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let fingerprint = story.content_fingerprint();
+        assert_eq!(fingerprint, story.clone().content_fingerprint());
+
+        let mut metrics_changed = story.clone();
+        metrics_changed.views += 1;
+        metrics_changed.comments += 1;
+        metrics_changed.chapters[0].views += 1;
+        assert_eq!(metrics_changed.content_fingerprint(), fingerprint);
+
+        let mut content_changed = story.clone();
+        content_changed.chapters[0].words += 1;
+        assert_ne!(content_changed.content_fingerprint(), fingerprint);
+
+        let mut content_changed = story;
+        content_changed.title.push('!');
+        assert_ne!(content_changed.content_fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn chapter_checksum() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let checksum = story.chapter_checksum(1612207).unwrap();
+        assert_eq!(checksum, 0x1607_ab6d_3352_63f2);
+        assert_eq!(story.chapter_checksum(428991), None);
+
+        let mut views_changed = story.clone();
+        views_changed.chapters[37].views += 1;
+        assert_eq!(views_changed.chapter_checksum(1612207), Some(checksum));
+
+        let mut content_changed = story;
+        content_changed.chapters[37].words += 1;
+        assert_ne!(content_changed.chapter_checksum(1612207), Some(checksum));
+        assert_eq!(
+            content_changed.chapter_checksum(1641318),
+            from_str(RESPONSE_SAMPLE).unwrap().chapter_checksum(1641318)
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_id_error_response() {
+        let response = r#"{
+    "error": "Invalid story id"
+}"#;
+
+        match from_str(response).unwrap_err() {
+            StoryError::InvalidId => {}
+            err => panic!("expected invalid ID error, got: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn deserialize_error_response() {
+        let response = r#"{
+    "error": "Some other error message"
+}"#;
+
+        match from_str(response).unwrap_err() {
+            StoryError::Api { message, code } => {
+                assert_eq!(message, "Some other error message");
+                assert_eq!(code, None);
+            }
+            err => panic!("expected API error, got: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn deserialization_error() {
+        let response = r#"{ "story": {} }"#;
+        match from_str(response).unwrap_err() {
+            StoryError::Json(_) => {}
+            err => panic!("expected a deserialization error, got: {err:?}"),
+        }
+
+        let response = "{";
+        match from_str(response).unwrap_err() {
+            StoryError::Json(_) => {}
+            err => panic!("expected a deserialization error, got: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn retryable_errors() {
+        assert!(from_str(r#"{ "error": "Try again later" }"#)
+            .unwrap_err()
+            .is_retryable());
+        assert!(!from_str(r#"{ "error": "Invalid story id" }"#)
+            .unwrap_err()
+            .is_retryable());
+        assert!(!from_str(r#"{ "story": {} }"#).unwrap_err().is_retryable());
+        assert!(!from_str("{}").unwrap_err().is_retryable());
+
+        for (code, retryable) in [(429, true), (500, true), (403, false), (404, false)] {
+            let response = format!(r#"{{ "error": "Try again later", "code": {code} }}"#);
+            assert_eq!(
+                from_str(&response).unwrap_err().is_retryable(),
+                retryable,
+                "{code}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_error_response() {
+        let response = r#"{ "error": "Invalid story id" }"#;
+        match parse_response(response).unwrap() {
+            Response::Error { message, code } => {
+                assert_eq!(message, "Invalid story id");
+                assert_eq!(code, None);
+            }
+            response => panic!("expected an error response, got: {response:?}"),
+        }
+
+        match parse_response(RESPONSE_SAMPLE).unwrap() {
+            Response::Story(story) => assert_eq!(story.id, 428991),
+            response => panic!("expected a story response, got: {response:?}"),
+        }
+    }
+
+    #[test]
+    fn unexpected_shape_error() {
+        for response in ["{}", r#"{ "stories": [] }"#, "[]", "null"] {
+            match from_str(response).unwrap_err() {
+                StoryError::UnexpectedShape(value) => {
+                    assert_eq!(value, serde_json::from_str::<Value>(response).unwrap())
+                }
+                err => panic!("expected an unexpected shape error, got: {err:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_unknown_response() {
+        let value = serde_json::json!({ "stories": [] });
+        let response = Response::Unknown(value.clone());
+        assert_eq!(serde_json::to_value(response).unwrap(), value);
+    }
+
+    #[test]
+    fn unpublished_error() {
+        let response = r#"{
+    "story": {
+        "author": {
+            "id": 1,
+            "name": "Author"
+        },
+        "chapter_count": 0,
+        "comments": 0,
+        "content_rating": 0,
+        "content_rating_text": "Everyone",
+        "date_modified": 0,
+        "description": "",
+        "dislikes": -1,
+        "id": 1,
+        "likes": -1,
+        "short_description": "",
+        "status": "Incomplete",
+        "title": "A story",
+        "total_views": 0,
+        "url": "https://www.fimfiction.net/story/1/a-story",
+        "views": 0,
+        "words": 0
+    }
+}"#;
+
+        match from_str(response).unwrap_err() {
+            StoryError::Unpublished(_) => {}
+            err => panic!("expected an unpublished error, got: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn deserialize_vote_aliases() {
+        let story = from_str(&renamed_sample("likes", "num_likes")).unwrap();
+        assert_eq!(story.likes, Some(1020));
+
+        let story = from_str(&renamed_sample("dislikes", "num_dislikes")).unwrap();
+        assert_eq!(story.dislikes, Some(8));
+    }
+
+    #[test]
+    fn deserialize_view_aliases() {
+        let story = from_str(&renamed_sample("views", "num_views")).unwrap();
+        assert_eq!(story.views, 10712);
+
+        let story = from_str(&renamed_sample("total_views", "total_num_views")).unwrap();
+        assert_eq!(story.total_views, 178800);
+    }
+
+    #[test]
+    fn deserialize_chapter_count_aliases() {
+        for alias in ["num_chapters", "number_of_chapters"] {
+            let story = from_str(&renamed_sample("chapter_count", alias)).unwrap();
+            assert_eq!(story.chapter_count, 40);
+
+            let value = serde_json::to_value(Response::from(story)).unwrap();
+            assert_eq!(value["story"]["chapter_count"], 40);
+            assert_eq!(value["story"].get(alias), None);
+        }
+    }
+
+    #[test]
+    fn completion_fraction() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.completion_fraction(), Some(1.0));
+
+        story.chapters.truncate(10);
+        assert_eq!(story.completion_fraction(), Some(0.25));
+
+        story.chapters.clear();
+        assert_eq!(story.completion_fraction(), None);
+    }
+
+    #[test]
+    fn comments_per_chapter() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_approx_eq!(story.comments_per_chapter().unwrap(), 48.225);
+
+        story.chapter_count = 0;
+        assert_eq!(story.comments_per_chapter(), None);
+    }
+
+    #[test]
+    fn recompute_totals() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        story.recompute_totals();
+        assert_eq!(story.chapter_count, 40);
+        assert_eq!(story.words, 275949);
+
+        let chapter = story.chapters[0].clone();
+        story.chapters.push(chapter);
+        story.recompute_totals();
+        assert_eq!(story.chapter_count, 41);
+        assert_eq!(story.words, 275949 + 5215);
+
+        story.chapters.clear();
+        story.recompute_totals();
+        assert_eq!(story.chapter_count, 0);
+        assert_eq!(story.words, 0);
+    }
+
+    #[test]
+    fn deserialize_missing_cover() {
+        let null_cover = edited_sample(|story| {
+            story.insert("image".to_string(), Value::Null);
+            story.insert("full_image".to_string(), Value::Null);
+        });
+        let missing_cover = edited_sample(|story| {
+            story.remove("image");
+            story.remove("full_image");
+        });
+
+        for response in [null_cover, missing_cover] {
+            let story = from_str(&response).unwrap();
+            assert_eq!(story.image, None);
+            assert_eq!(story.full_image, None);
+
+            let value = serde_json::to_value(Response::Story(story)).unwrap();
+            assert_eq!(value["story"]["image"], Value::Null);
+            assert_eq!(value["story"]["full_image"], Value::Null);
+        }
+    }
+
+    #[test]
+    fn deserialize_nested_rating() {
+        let response = edited_sample(|story| {
+            story.remove("content_rating");
+            story.remove("content_rating_text");
+            story.insert(
+                "rating".to_string(),
+                serde_json::json!({ "value": 1, "name": "Teen" }),
+            );
+        });
+
+        let story = from_str(&response).unwrap();
+        assert_eq!(story.content_rating, StoryRating::Teen);
+
+        let value = serde_json::to_value(Response::Story(story)).unwrap();
+        assert_eq!(value["story"]["content_rating"], 1);
+        assert_eq!(value["story"]["content_rating_text"], "Teen");
+        assert_eq!(value["story"].get("rating"), None);
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn json_schema() {
+        let schema = serde_json::to_value(Story::as_json_schema()).unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+
+        let value: Value = serde_json::from_str(RESPONSE_SAMPLE).unwrap();
+        for key in value["story"].as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "missing `{key}` in schema");
+        }
+        assert_eq!(properties["likes"]["type"], "integer");
+        assert_eq!(
+            properties["content_rating_text"]["$ref"],
+            "#/definitions/StoryRatingText"
+        );
+    }
+
+    #[test]
+    fn deserialize_missing_author() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(!story.author.is_unknown());
+        assert_eq!(story.author_or_unknown(), "Rambling Writer");
+
+        let response = edited_sample(|story| {
+            story.remove("author");
+        });
+        let story = from_str(&response).unwrap();
+        assert!(story.author.is_unknown());
+        assert_eq!(story.author_or_unknown(), "Unknown");
+    }
+
+    #[test]
+    fn chapter_lookup() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let chapter = story.chapter_by_id(1375472).unwrap();
+        assert_eq!(chapter.title, "Source Incantations: Debugging");
+        assert!(story.chapter_by_id(428991).is_none());
+
+        let index = story.chapter_index();
+        assert_eq!(index.len(), 40);
+        assert_eq!(index[&1375472], 9);
+        assert_eq!(index.get(&428991), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn is_stale() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let max_age = chrono::Duration::hours(6);
+
+        assert!(story.is_stale(max_age));
+        assert!(!story.is_stale_at(story.date_modified + chrono::Duration::hours(5), max_age));
+        assert!(story.is_stale_at(story.date_modified + chrono::Duration::hours(7), max_age));
+        assert!(!story.is_stale_at(story.date_modified - chrono::Duration::hours(1), max_age));
+    }
+
+    #[test]
+    fn serde_reversible_unicode() {
+        let response = edited_sample(|story| {
+            story.insert("title".to_string(), "夢の中で 🌙 Tantabus".into());
+            story.insert("short_description".to_string(), "Луна 🦄 и сны".into());
+        });
+        let value: Value = serde_json::from_str(&response).unwrap();
+
+        let story = from_str(&response).unwrap();
+        assert_eq!(story.title, "夢の中で 🌙 Tantabus");
+
+        let serialized = to_string(story).unwrap();
+        let serialized_value: Value = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(value, serialized_value);
+        assert!(serialized.contains("夢の中で 🌙 Tantabus"));
+    }
+
+    #[cfg(feature = "opds")]
+    #[test]
+    fn opds_entry() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let entry = story.to_opds_entry();
+
+        assert!(entry.starts_with("<entry>") && entry.ends_with("</entry>"));
+        assert!(entry.contains("<title>How the Tantabus Parses Sleep</title>"));
+        assert!(entry.contains("<author><name>Rambling Writer</name></author>"));
+        assert!(entry.contains("<updated>2022-12-15T16:41:54Z</updated>"));
+        assert!(entry.contains(
+            r#"<link rel="http://opds-spec.org/image" href="https://cdn-img.fimfiction.net/story/iwqb-1673322192-428991-full"/>"#
+        ));
+    }
+
+    #[test]
+    fn word_density() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        let counts = story.chapter_word_counts();
+        assert_eq!(counts.len(), 40);
+        assert_eq!(counts[..3], [5215, 4094, 6734]);
+        assert_eq!(story.word_density_stats(), (4043, 13085, 6898.725));
+
+        story.chapters.clear();
+        assert!(story.chapter_word_counts().is_empty());
+        assert_eq!(story.word_density_stats(), (0, 0, 0.0));
+    }
+
+    #[test]
+    fn collect_stories_by_id() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+
+        let mut older = story.clone();
+        older.title = "Older".to_string();
+        older.date_modified = story.chapters[0].date_modified;
+
+        let mut other = story.clone();
+        other.id = 1;
+
+        let map = collect_by_id([older.clone(), story.clone(), other, older]);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&428991].title, story.title);
+        assert_eq!(map[&1].id, 1);
+    }
+
+    #[test]
+    fn serialize_compact() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        let value: Value = serde_json::from_str(RESPONSE_SAMPLE).unwrap();
+        let compact: Value =
+            serde_json::from_str(&to_string_compact(story.clone()).unwrap()).unwrap();
+        assert_eq!(compact, value);
+
+        story.image = None;
+        story.full_image = None;
+        let full: Value = serde_json::from_str(&to_string(story.clone()).unwrap()).unwrap();
+        let compact: Value = serde_json::from_str(&to_string_compact(story).unwrap()).unwrap();
+        assert_eq!(compact["story"].get("image"), None);
+        assert_eq!(compact["story"].get("full_image"), None);
+        assert_eq!(compact["story"]["title"], value["story"]["title"]);
+
+        let keys = |value: &Value| {
+            let story = value["story"].as_object().unwrap();
+            story
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(keys(&compact), keys(&full));
+        assert_eq!(keys(&compact)[0], "id");
+    }
+
+    #[test]
+    fn audiobook_duration() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(
+            story.audiobook_duration(150),
+            Duration::from_millis(110_379_600)
+        );
+        assert_eq!(
+            story.audiobook_duration_default(),
+            story.audiobook_duration(150)
+        );
+        assert_eq!(story.audiobook_duration(0), Duration::ZERO);
+
+        let durations = story.chapter_audiobook_durations();
+        assert_eq!(durations.len(), 40);
+        assert_eq!(durations[0], Duration::from_millis(2_086_000));
+        assert_eq!(story.chapters[0].audiobook_duration_default(), durations[0]);
+        assert_eq!(story.chapter_audiobook_durations_with(150), durations);
+
+        let mut story = story;
+        story.words = u64::MAX;
+        assert_eq!(story.audiobook_duration(1), Duration::from_millis(u64::MAX));
+
+        story.words = u64::MAX / 1000;
+        assert_eq!(
+            story.audiobook_duration(150),
+            Duration::from_millis(u64::MAX / 1000 * 400)
+        );
+    }
+
+    #[test]
+    fn content_eq() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+
+        let mut metrics_changed = story.clone();
+        metrics_changed.views += 1;
+        metrics_changed.likes = None;
+        metrics_changed.chapters[0].views += 1;
+        assert!(story.content_eq(&metrics_changed));
+
+        let mut content_changed = story.clone();
+        content_changed.chapters[1].title.push('!');
+        assert!(!story.content_eq(&content_changed));
+
+        let mut content_changed = story.clone();
+        content_changed.chapters.pop();
+        assert!(!story.content_eq(&content_changed));
+    }
+
+    #[test]
+    fn summary() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.summary(10), story.short_description);
+
+        story.short_description.clear();
+        assert_eq!(story.summary(30), "What started with laziness…");
+        assert_eq!(story.summary(27), "What started with laziness…");
+        assert_eq!(story.summary(1), "…");
+        assert_eq!(story.summary(0), "");
+        assert_eq!(story.summary(usize::MAX), story.description);
+
+        story.description = "夢の中で🌙".to_string();
+        assert_eq!(story.summary(4), "夢の中…");
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_story() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let bytes: Vec<u8> = (0..=u8::MAX).cycle().take(4096).collect();
+        let mut unstructured = Unstructured::new(&bytes);
+        let story = Story::arbitrary(&mut unstructured).unwrap();
+
+        let value = serde_json::to_value(Response::Story(story.clone())).unwrap();
+        let deserialized: Response = serde_json::from_value(value).unwrap();
+        match deserialized {
+            Response::Story(deserialized) => {
+                assert!(story.content_eq(&deserialized));
+                assert_eq!(story.date_modified, deserialized.date_modified);
+            }
+            response => panic!("expected a story, got: {response:?}"),
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    ::proptest::proptest! {
+        #[test]
+        fn proptest_story_reversible(story in crate::proptest::story_strategy()) {
+            let serialized = to_string(story.clone()).unwrap();
+            let deserialized = from_str(&serialized).unwrap();
+            ::proptest::prop_assert!(story.content_eq(&deserialized));
+            ::proptest::prop_assert_eq!(deserialized.words, story.words);
+        }
+    }
+
+    #[test]
+    fn tags_of_kind() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(story.tags.is_empty());
+
+        let response = edited_sample(|story| {
+            story.insert(
+                "tags".to_string(),
+                serde_json::json!([
+                    { "name": "Princess Luna", "type": "character" },
+                    { "name": "Comedy", "type": "genre" },
+                    { "name": "Tantabus", "type": "character" },
+                ]),
+            );
+        });
+        let story = from_str(&response).unwrap();
+
+        let characters: Vec<_> = story
+            .tags_of_kind(TagKind::Character)
+            .map(|tag| tag.name.as_str())
+            .collect();
+        assert_eq!(characters, ["Princess Luna", "Tantabus"]);
+        assert_eq!(story.tags_of_kind(TagKind::Warning).count(), 0);
+    }
+
+    #[test]
+    fn into_parts() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let (metadata, chapters) = story.into_parts();
+
+        assert!(metadata.chapters.is_empty());
+        assert_eq!(metadata.chapter_count, 40);
+        assert_eq!(chapters.len(), 40);
+        assert_eq!(chapters[0].id, 1273271);
+    }
+
+    #[cfg(feature = "opf")]
+    #[test]
+    fn opf_document() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        story.tags.push(Tag {
+            name: "Slice of Life".to_string(),
+            kind: TagKind::Genre,
+        });
+        let opf = story.to_opf();
+
+        assert!(opf.starts_with("<?xml"));
+        assert!(opf.contains("<dc:title>How the Tantabus Parses Sleep</dc:title>"));
+        assert!(opf.contains(r#"<dc:creator opf:role="aut">Rambling Writer</dc:creator>"#));
+        assert!(opf.contains("<dc:description>What started with laziness"));
+        assert!(opf.contains("Don&apos;t tell Luna about that last part.</dc:description>"));
+        assert!(opf.contains("<dc:subject>Slice of Life</dc:subject>"));
+        assert!(opf.ends_with("</package>\n"));
+    }
+
+    #[test]
+    fn votes() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.vote_count(), Some(1028));
+        assert_eq!(story.net_votes(), Some(1012));
+
+        story.likes = Some(u32::MAX);
+        assert_eq!(story.vote_count(), Some(u32::MAX));
+        story.likes = Some(0);
+        assert_eq!(story.net_votes(), Some(-8));
+
+        story.dislikes = None;
+        assert_eq!(story.vote_count(), None);
+        assert_eq!(story.net_votes(), None);
+    }
+
+    #[cfg(feature = "cow")]
+    #[test]
+    fn deserialize_borrowed() {
+        use std::borrow::Cow;
+
+        let story = cow::from_str_borrowed(RESPONSE_SAMPLE).unwrap();
+        assert!(matches!(story.title, Cow::Borrowed(_)));
+        assert!(matches!(story.author.name, Cow::Borrowed(_)));
+        assert!(matches!(story.description, Cow::Owned(_)));
+        assert_eq!(story.chapters.len(), 40);
+
+        let owned = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(story.into_owned().content_eq(&owned));
+
+        match cow::from_str_borrowed(r#"{ "error": "Invalid story id" }"#).unwrap_err() {
+            StoryError::InvalidId => {}
+            err => panic!("expected invalid ID error, got: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn word_count_percentile() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let corpus: Vec<Story> = [1000, 275949, 300000, 5000]
+            .into_iter()
+            .map(|words| Story {
+                words,
+                ..story.clone()
+            })
+            .collect();
+
+        assert_eq!(story.word_count_percentile(&corpus), 0.5);
+        assert_eq!(corpus[0].word_count_percentile(&corpus), 0.0);
+        assert_eq!(corpus[2].word_count_percentile(&corpus), 0.75);
+        assert_eq!(story.word_count_percentile(&[]), 0.0);
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn known_story_fields() {
+        let value: Value = serde_json::from_str(RESPONSE_SAMPLE).unwrap();
+        for key in value["story"].as_object().unwrap().keys() {
+            assert!(
+                KNOWN_STORY_FIELDS.contains(&key.as_str()),
+                "`{key}` is unknown"
+            );
+        }
+
+        let mut known = KNOWN_STORY_FIELDS.to_vec();
+        known.sort_unstable();
+        let mut accepted = accepted_fields::<Story>();
+        accepted.sort_unstable();
+        assert_eq!(known, accepted);
+    }
+
+    #[test]
+    fn sort_by_key() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let mut stories: Vec<Story> = [(3, 100, None), (1, 500, Some(10)), (2, 100, Some(20))]
+            .into_iter()
+            .map(|(id, views, likes)| Story {
+                id,
+                views,
+                likes,
+                ..story.clone()
+            })
+            .collect();
+        let ids = |stories: &[Story]| stories.iter().map(|story| story.id).collect::<Vec<_>>();
+
+        stories.sort_by_key(|story| story.sort_key(SortBy::Views));
+        assert_eq!(ids(&stories), [1, 2, 3]);
+
+        stories.sort_by_key(|story| story.sort_key(SortBy::Rating));
+        assert_eq!(ids(&stories), [2, 1, 3]);
+
+        stories.sort_by_key(|story| story.sort_key(SortBy::Updated));
+        assert_eq!(ids(&stories), [1, 2, 3]);
+    }
+
+    #[test]
+    fn approximate_size_bytes() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let size = story.approximate_size_bytes();
+        assert!(size > RESPONSE_SAMPLE.len() / 2);
+
+        let (metadata, chapters) = story.into_parts();
+        let chapters_size: usize = chapters.iter().map(Chapter::approximate_size_bytes).sum();
+        assert_eq!(metadata.approximate_size_bytes() + chapters_size, size);
+    }
+
+    #[test]
+    fn description_first_paragraph() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(
+            story.description_first_paragraph(),
+            "What started with laziness and a tiny slip-up in arcane intelligence creation has blossomed into its own being. With Luna's blessing, the second Tantabus is free to flit around the dreamscape as it pleases, unburdened by oversight. And what pleases it is making good dreams. Who couldn't be happy by making others happy?"
+        );
+
+        story.description = "A single paragraph.\r\nWith a line break.".to_string();
+        assert_eq!(
+            story.description_first_paragraph(),
+            "A single paragraph.\r\nWith a line break."
+        );
+    }
+
+    #[test]
+    fn like_ratio() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_approx_eq!(story.like_ratio().unwrap(), 1020.0 / 1028.0);
+        assert_eq!(story.like_ratio_rounded(0), Some(1.0));
+        assert_eq!(story.like_ratio_rounded(2), Some(0.99));
+        assert_eq!(story.like_ratio_rounded(3), Some(0.992));
+
+        story.likes = Some(0);
+        story.dislikes = Some(0);
+        assert_eq!(story.like_ratio(), None);
+
+        story.likes = None;
+        story.dislikes = None;
+        assert_eq!(story.like_ratio_rounded(2), None);
+    }
+
+    #[test]
+    fn views_reconciliation() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        let report = story.views_reconciliation();
+        assert_eq!(
+            report,
+            ViewsReport {
+                story_views: 10712,
+                chapter_max_views: 10712,
+                story_total_views: 178800,
+                chapter_total_views: 178810,
+            }
+        );
+        assert_eq!(report.views_delta(), 0);
+        assert_eq!(report.total_views_delta(), -10);
+        assert!(!report.is_consistent());
+
+        story.total_views = 178810;
+        assert!(story.views_reconciliation().is_consistent());
+
+        story.chapters.clear();
+        let report = story.views_reconciliation();
+        assert_eq!(report.views_delta(), 10712);
+        assert_eq!(report.total_views_delta(), 178810);
+    }
+
+    #[test]
+    fn validate() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.validate(), Ok(()));
+
+        story.words = 0;
+        story.chapter_count = 41;
+        story.image = Some("https://example.com/cover.png".to_string());
+        story.chapters[2].link = "/story/428991/3".to_string();
+        let errors = story.validate().unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                ValidationError::WordsMismatch {
+                    words: 0,
+                    chapter_words: 275949
+                },
+                ValidationError::ChapterCountMismatch {
+                    chapter_count: 41,
+                    chapters: 40
+                },
+                ValidationError::ForeignUrl {
+                    field: "image".to_string(),
+                    url: "https://example.com/cover.png".to_string()
+                },
+                ValidationError::ForeignUrl {
+                    field: "chapters[2].link".to_string(),
+                    url: "/story/428991/3".to_string()
+                },
+            ]
+        );
+        let fields: Vec<_> = errors.iter().map(ValidationError::field).collect();
+        assert_eq!(
+            fields,
+            ["words", "chapter_count", "image", "chapters[2].link"]
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.comments, 1929);
+
+        let story = from_str(&renamed_sample("comments", "num_comments")).unwrap();
+        assert_eq!(story.comments, 1929);
+
+        let story = from_str(&edited_sample(|story| {
+            story.remove("comments");
+        }))
+        .unwrap();
+        assert_eq!(story.comments, 0);
+        assert_eq!(story.comments_per_chapter(), Some(0.0));
+
+        let value = serde_json::to_value(Response::from(story)).unwrap();
+        assert_eq!(value["story"]["comments"], 0);
+    }
+
     #[test]
-    fn deserialize_story_response() {
-        from_str(RESPONSE_SAMPLE).expect("response should be deserialized into a Story");
+    fn pacing() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(story.chapters[0].is_long(5000));
+        assert!(!story.chapters[0].is_long(5215));
+
+        let ids = |chapters: Vec<&Chapter>| -> Vec<Id> {
+            chapters.into_iter().map(|chapter| chapter.id).collect()
+        };
+        assert_eq!(ids(story.pacing_outliers(2.0)), [1612207]);
+        assert_eq!(ids(story.pacing_outliers(1.5)), [1504708, 1584499, 1612207]);
+
+        story.chapters.truncate(2);
+        assert!(story.pacing_outliers(0.0).is_empty());
     }
 
     #[test]
-    fn serialize_story_response() {
-        let story =
-            from_str(RESPONSE_SAMPLE).expect("response should be deserialized into a Story");
-        to_string(story).expect("Story should be serializable as a String");
+    fn story_card() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let card = story.to_card();
+        assert_eq!(
+            serde_json::to_value(card).unwrap(),
+            serde_json::json!({
+                "id": 428991,
+                "title": "How the Tantabus Parses Sleep",
+                "author": { "id": 253168, "name": "Rambling Writer" },
+                "image": "https://cdn-img.fimfiction.net/story/iwqb-1673322192-428991-medium",
+                "words": 275949,
+                "status": "Incomplete",
+                "like_ratio": 1020.0 / 1028.0
+            })
+        );
     }
 
     #[test]
-    fn serde_reversible() {
-        let value: Value = serde_json::from_str(RESPONSE_SAMPLE).unwrap();
+    fn deserialize_camel_case_aliases() {
+        fn to_camel_case(key: &str) -> String {
+            let mut parts = key.split('_');
+            let mut camel = parts.next().unwrap_or_default().to_string();
+            for part in parts {
+                let mut chars = part.chars();
+                camel.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+                camel.push_str(chars.as_str());
+            }
+            camel
+        }
 
-        let story: Story =
-            from_str(RESPONSE_SAMPLE).expect("response should be deserialized into a Story");
-        let serialized_value = serde_json::to_value(Response::Story(story)).unwrap();
+        fn camel_case_keys(object: &mut Map<String, Value>) {
+            *object = std::mem::take(object)
+                .into_iter()
+                .map(|(key, value)| (to_camel_case(&key), value))
+                .collect();
+        }
 
-        assert_eq!(value, serialized_value);
+        let sample = edited_sample(|story| {
+            camel_case_keys(story);
+            for chapter in story["chapters"].as_array_mut().unwrap() {
+                camel_case_keys(chapter.as_object_mut().unwrap());
+            }
+        });
+        assert!(sample.contains("\"shortDescription\""));
+
+        let story = from_str(&sample).expect("camelCase keys should be deserializable");
+        let expected = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(
+            serde_json::to_value(story).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
     }
 
     #[test]
-    fn deserialize_invalid_id_error_response() {
-        let response = r#"{
-    "error": "Invalid story id"
-}"#;
+    fn serialize_api_keys() {
+        let keys = |value: &Value| -> Vec<String> {
+            let mut keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
 
-        match from_str(response).unwrap_err() {
-            StoryError::InvalidId => {}
-            err => panic!("expected invalid ID error, got: {err:?}"),
+        let response: Value = serde_json::from_str(RESPONSE_SAMPLE).unwrap();
+        let story = serde_json::to_value(from_str(RESPONSE_SAMPLE).unwrap()).unwrap();
+        assert_eq!(keys(&story), keys(&response["story"]));
+        assert_eq!(
+            keys(&story["chapters"][0]),
+            keys(&response["story"]["chapters"][0])
+        );
+    }
+
+    #[test]
+    fn groups() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(story.groups.is_empty());
+        assert!(!story.is_in_group(1));
+
+        let story = from_str(&edited_sample(|story| {
+            story.insert(
+                "groups".to_string(),
+                serde_json::json!([{
+                    "id": 1,
+                    "name": "Group",
+                    "url": "https://www.fimfiction.net/group/1/group"
+                }]),
+            );
+        }))
+        .unwrap();
+        assert_eq!(
+            story.groups,
+            [GroupLink {
+                id: 1,
+                name: "Group".to_string(),
+                url: "https://www.fimfiction.net/group/1/group".to_string()
+            }]
+        );
+        assert!(story.is_in_group(1));
+        assert!(!story.is_in_group(2));
+    }
+
+    #[test]
+    fn word_count_histogram() {
+        let sample = from_str(RESPONSE_SAMPLE).unwrap();
+        let stories: Vec<_> = [0, 999, 1000, 1999, 2000, 5500]
+            .into_iter()
+            .map(|words| Story {
+                words,
+                ..sample.clone()
+            })
+            .collect();
+
+        assert_eq!(
+            super::word_count_histogram(&stories, 1000),
+            BTreeMap::from([(0, 2), (1000, 2), (2000, 1), (5000, 1)])
+        );
+        assert_eq!(
+            super::word_count_histogram(&stories, 2000),
+            BTreeMap::from([(0, 4), (2000, 1), (4000, 1)])
+        );
+        assert_eq!(super::word_count_histogram(&stories, 0).len(), 6);
+        assert!(super::word_count_histogram(&[], 1000).is_empty());
+    }
+
+    #[test]
+    fn added_since() {
+        let current = from_str(RESPONSE_SAMPLE).unwrap();
+        let (mut previous, mut chapters) = current.clone().into_parts();
+        chapters.truncate(38);
+        previous.chapters = chapters.into();
+        previous.recompute_totals();
+
+        assert_eq!(current.words_added_since(&previous), 4043 + 6256);
+        assert_eq!(current.chapters_added_since(&previous), 2);
+        assert_eq!(previous.words_added_since(&current), -(4043 + 6256));
+        assert_eq!(previous.chapters_added_since(&current), -2);
+        assert_eq!(current.words_added_since(&current), 0);
+    }
+
+    #[test]
+    fn search() {
+        let sample = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(sample.matches_query("TANTABUS"));
+        assert!(sample.matches_query("don't tell luna"));
+        assert!(sample.matches_query(""));
+        assert!(!sample.matches_query("Celestia"));
+
+        let mut marked_up = sample.clone();
+        marked_up.id += 1;
+        marked_up.title = "Untitled".to_string();
+        marked_up.short_description = String::new();
+        marked_up.description = "A story about [i]Princess[/i] Celestia.".to_string();
+        assert!(marked_up.matches_query("princess celestia"));
+
+        let stories = [sample, marked_up];
+        let ids = |query| -> Vec<Id> {
+            super::search(&stories, query)
+                .into_iter()
+                .map(|story| story.id)
+                .collect()
+        };
+        assert_eq!(ids("celestia"), [428992]);
+        assert_eq!(ids("princess"), [428992]);
+        assert_eq!(ids("luna"), [428991]);
+        assert_eq!(ids("a"), [428991, 428992]);
+        assert!(ids("discord").is_empty());
+    }
+
+    #[test]
+    fn deserialize_numeric_rating_only() {
+        let response = edited_sample(|story| {
+            story.remove("content_rating_text");
+        });
+
+        let story = from_str(&response).expect("content_rating_text should be optional");
+        assert_eq!(story.content_rating, StoryRating::Everyone);
+
+        let value = serde_json::to_value(Response::Story(story)).unwrap();
+        assert_eq!(value["story"]["content_rating_text"], "Everyone");
+    }
+
+    #[test]
+    fn normalize_urls_https() {
+        let sample = from_str(RESPONSE_SAMPLE).unwrap();
+        let mut story = sample.clone();
+        story.url = story.url.replacen("https", "http", 1);
+        story.image = story.image.map(|image| image.replacen("https", "http", 1));
+        story.full_image = Some("http://example.com/cover.png".to_string());
+        story.chapters[0].link = story.chapters[0].link.replacen("https", "http", 1);
+
+        story.normalize_urls_https();
+        assert_eq!(story.url, sample.url);
+        assert_eq!(story.image, sample.image);
+        assert_eq!(
+            story.full_image.as_deref(),
+            Some("http://example.com/cover.png")
+        );
+        assert_eq!(story.chapters[0].link, sample.chapters[0].link);
+    }
+
+    #[test]
+    fn to_markdown() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        story.short_description = "A *dream* construct.\r\nSecond line.".to_string();
+        story.chapters.truncate(2);
+
+        assert_eq!(
+            story.to_markdown(),
+            "**[How the Tantabus Parses Sleep](<https://www.fimfiction.net/story/428991/how-the-tantabus-parses-sleep>)** by Rambling Writer\n\
+             \n\
+             Incomplete · 275949 words\n\
+             \n\
+             > A \\*dream\\* construct.\n\
+             > Second line.\n\
+             \n\
+             1. [Nightmares and the Deletion Thereof](<https://www.fimfiction.net/story/428991/1/how-the-tantabus-parses-sleep/nightmares-and-the-deletion-thereof>)\n\
+             2. [Robbery, He Dreamed](<https://www.fimfiction.net/story/428991/2/how-the-tantabus-parses-sleep/robbery-he-dreamed>)\n"
+        );
+    }
+
+    #[cfg(feature = "cow")]
+    #[test]
+    fn borrowed_eq_owned() {
+        let borrowed = cow::from_str_borrowed(RESPONSE_SAMPLE).unwrap();
+        let mut owned = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(borrowed == owned);
+        assert!(owned == borrowed);
+        assert!(borrowed.author == owned.author);
+        assert!(borrowed.chapters[0] == owned.chapters[0]);
+
+        owned.chapters[39].views += 1;
+        assert!(borrowed != owned);
+        assert!(owned != borrowed);
+
+        // Compares every field, including any that `PartialEq` could miss.
+        let owned = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(
+            serde_json::to_value(&borrowed).unwrap(),
+            serde_json::to_value(&owned).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(borrowed.into_owned()).unwrap(),
+            serde_json::to_value(owned).unwrap()
+        );
+
+        let bare_author = edited_sample(|story| {
+            story.insert("author".to_string(), Value::from(253168));
+        });
+        let borrowed = cow::from_str_borrowed(&bare_author).unwrap();
+        assert_eq!(borrowed.author.id, 253168);
+        assert_eq!(borrowed.author.name, "");
+        assert!(borrowed == from_str(&bare_author).unwrap());
+    }
+
+    #[cfg(feature = "cow")]
+    #[test]
+    fn borrowed_optional_text() {
+        use std::borrow::Cow;
+
+        let with_language = edited_sample(|story| {
+            story.insert("language".to_string(), Value::from("English"));
+        });
+        let borrowed = cow::from_str_borrowed(&with_language).unwrap();
+        assert!(matches!(borrowed.image, Some(Cow::Borrowed(_))));
+        assert!(matches!(borrowed.full_image, Some(Cow::Borrowed(_))));
+        assert!(matches!(borrowed.language, Some(Cow::Borrowed("English"))));
+
+        let without = edited_sample(|story| {
+            story.insert("image".to_string(), Value::Null);
+            story.remove("full_image");
+        });
+        let borrowed = cow::from_str_borrowed(&without).unwrap();
+        assert_eq!(borrowed.image, None);
+        assert_eq!(borrowed.full_image, None);
+        assert_eq!(borrowed.language, None);
+    }
+
+    #[test]
+    fn chapters_updated_after() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let ids = |chapters: Vec<&Chapter>| -> Vec<Id> {
+            chapters.into_iter().map(|chapter| chapter.id).collect()
+        };
+
+        let since = timestamp(&story.chapters[38].date_modified);
+        assert_eq!(
+            ids(story.chapters_updated_after(since - 1)),
+            [1618997, 1641318]
+        );
+        assert_eq!(ids(story.chapters_updated_after(since)), [1641318]);
+        assert_eq!(story.chapters_updated_after(0).len(), 40);
+        assert!(story.chapters_updated_after(i64::MAX).is_empty());
+
+        #[cfg(feature = "chrono")]
+        assert_eq!(
+            ids(story.chapters_updated_after_datetime(story.chapters[38].date_modified)),
+            [1641318]
+        );
+    }
+
+    #[test]
+    fn publication_state() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.publication_state, None);
+        assert!(story.is_published());
+
+        for (state, expected, published) in [
+            ("published", PublicationState::Published, true),
+            ("unpublished", PublicationState::Unpublished, false),
+            ("pending", PublicationState::Pending, false),
+        ] {
+            let story = from_str(&edited_sample(|story| {
+                story.insert("publication_state".to_string(), Value::from(state));
+            }))
+            .unwrap();
+            assert_eq!(story.publication_state, Some(expected));
+            assert_eq!(story.is_published(), published);
         }
     }
 
     #[test]
-    fn deserialize_error_response() {
-        let response = r#"{
-    "error": "Some other error message"
-}"#;
+    fn truncate_and_take_chapters() {
+        let sample = from_str(RESPONSE_SAMPLE).unwrap();
 
-        match from_str(response).unwrap_err() {
-            StoryError::Api(msg) => assert_eq!(msg, "Some other error message"),
-            err => panic!("expected API error, got: {err:?}"),
+        let mut story = sample.clone();
+        story.truncate_chapters(3);
+        assert_eq!(story.chapters.len(), 3);
+        assert_eq!(story.chapter_count, 40);
+        assert_eq!(story.chapters[2].id, sample.chapters[2].id);
+
+        let mut story = sample.clone();
+        let taken = story.take_chapters(30);
+        assert_eq!(taken.len(), 30);
+        assert_eq!(taken[0].id, sample.chapters[0].id);
+        assert_eq!(story.chapters.len(), 10);
+        assert_eq!(story.chapters[0].id, sample.chapters[30].id);
+        assert_eq!(story.chapter_count, 40);
+
+        assert_eq!(story.take_chapters(20).len(), 10);
+        assert!(story.chapters.is_empty());
+        assert!(story.take_chapters(1).is_empty());
+    }
+
+    #[test]
+    fn language() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.language, None);
+        assert!(story.is_english());
+
+        for (language, english) in [
+            ("English", true),
+            ("en-US", true),
+            ("Spanish", false),
+            ("Enochian", false),
+        ] {
+            let story = from_str(&edited_sample(|story| {
+                story.insert("language".to_string(), Value::from(language));
+            }))
+            .unwrap();
+            assert_eq!(story.language.as_deref(), Some(language));
+            assert_eq!(story.is_english(), english);
         }
     }
 
     #[test]
-    fn deserialization_error() {
-        let response = "{}";
+    fn diff_since() {
+        let previous = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(previous.diff_since(&previous).is_empty());
+
+        let mut current = previous.clone();
+        current.status = StoryStatus::Complete;
+        current.content_rating = StoryRating::Mature;
+        current.words += 1000;
+
+        let diff = current.diff_since(&previous);
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff,
+            StoryDiff {
+                words_added: 1000,
+                chapters_added: 0,
+                rating_change: Some((StoryRating::Everyone, StoryRating::Mature)),
+                status_change: Some((StoryStatus::Incomplete, StoryStatus::Complete)),
+            }
+        );
+        assert!(!diff.has_suspicious_status_change());
+        assert!(previous.diff_since(&current).has_suspicious_status_change());
+    }
+
+    #[test]
+    fn chapter_links_and_ids() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+
+        let links: Vec<_> = story.chapter_links().collect();
+        assert_eq!(links.len(), 40);
+        assert_eq!(
+            links[1],
+            "https://www.fimfiction.net/story/428991/2/how-the-tantabus-parses-sleep/robbery-he-dreamed"
+        );
+
+        let ids: Vec<_> = story.chapter_ids().collect();
+        assert_eq!(ids.len(), 40);
+        assert_eq!(ids[38..], [1618997, 1641318]);
+    }
+
+    #[test]
+    fn deserialize_hidden_views() {
+        let response = edited_sample(|story| {
+            story.insert("views".to_string(), Value::from(-1));
+            story.insert("total_views".to_string(), Value::from(-1));
+            story["chapters"][0]["views"] = Value::from(-1);
+        });
+
+        let story = from_str(&response).expect("hidden views should be deserializable");
+        assert_eq!(story.views, 0);
+        assert_eq!(story.total_views, 0);
+        assert_eq!(story.chapters[0].views, 0);
+        assert_eq!(story.chapters[1].views, 7059);
+    }
+
+    #[cfg(feature = "rusqlite")]
+    #[test]
+    fn sqlite_row() {
+        use sqlite::STORY_COLUMNS;
+
+        let connection = rusqlite::Connection::open_in_memory().unwrap();
+        connection
+            .execute(
+                &format!("CREATE TABLE stories ({})", STORY_COLUMNS.join(", ")),
+                [],
+            )
+            .unwrap();
+
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        story.dislikes = None;
+        story.publication_state = Some(PublicationState::Published);
+
+        let placeholders = vec!["?"; STORY_COLUMNS.len()].join(", ");
+        let mut insert = connection
+            .prepare(&format!("INSERT INTO stories VALUES ({placeholders})"))
+            .unwrap();
+        story.bind_to(&mut insert).unwrap();
+        assert_eq!(insert.raw_execute().unwrap(), 1);
+
+        let stored = connection
+            .query_row("SELECT * FROM stories", [], Story::from_row)
+            .unwrap();
+        story.chapters.clear();
+        assert_eq!(
+            serde_json::to_value(stored).unwrap(),
+            serde_json::to_value(story).unwrap()
+        );
+    }
+
+    #[test]
+    fn duplicate_chapters() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(!story.has_duplicate_chapters());
+
+        let duplicate = Chapter {
+            title: "Repeated".to_string(),
+            ..story.chapters[1].clone()
+        };
+        story.chapters.push(duplicate);
+        assert!(story.has_duplicate_chapters());
+
+        story.dedupe_chapters();
+        assert!(!story.has_duplicate_chapters());
+        assert_eq!(story.chapters.len(), 40);
+        assert_ne!(story.chapters[1].title, "Repeated");
+        assert!(story
+            .chapters
+            .iter()
+            .all(|chapter| chapter.title != "Repeated"));
+    }
+
+    #[test]
+    fn chapter_date_range() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.chapter_date_range(), Some((1557744585, 1678750782)));
+
+        #[cfg(feature = "chrono")]
+        assert_eq!(
+            story.chapter_date_range_datetime(),
+            Some((
+                story.chapters[2].date_modified,
+                story.chapters[39].date_modified
+            ))
+        );
+
+        story.chapters.clear();
+        assert_eq!(story.chapter_date_range(), None);
+    }
+
+    #[test]
+    fn response_from_story() {
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let response: Response = story.clone().into();
+        assert!(matches!(&response, Response::Story(wrapped) if wrapped.id == story.id));
+        assert_eq!(
+            serde_json::to_string(&response).unwrap(),
+            to_string(story).unwrap()
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn comments_per_day() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        let oldest = story.chapters[2].date_modified;
+        assert_approx_eq!(
+            story
+                .comments_per_day_at(oldest + chrono::Duration::days(10))
+                .unwrap(),
+            192.9
+        );
+        assert_eq!(story.comments_per_day_at(oldest), None);
+        assert_eq!(
+            story.comments_per_day_at(oldest - chrono::Duration::days(1)),
+            None
+        );
+
+        story.chapters.clear();
+        assert_eq!(story.comments_per_day(), None);
+    }
+
+    #[test]
+    fn append_chapters() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        let ids: Vec<Id> = story.chapter_ids().collect();
+        let chapters = story.take_chapters(40);
+        story.chapter_count = 0;
+
+        story.append_chapters(chapters[..30].to_vec());
+        assert_eq!(story.chapters.len(), 30);
+        assert_eq!(story.chapter_count, 30);
+
+        // Overlaps with the first page and repeats a chapter of its own.
+        let mut second_page = chapters[25..].to_vec();
+        second_page.push(chapters[39].clone());
+        story.append_chapters(second_page);
+        assert_eq!(story.chapters.len(), 40);
+        assert_eq!(story.chapter_count, 40);
+        assert_eq!(story.chapter_ids().collect::<Vec<_>>(), ids);
+    }
+
+    #[test]
+    fn set_rating() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        story.set_rating(StoryRating::Mature);
+        assert_eq!(story.content_rating, StoryRating::Mature);
+
+        let value = serde_json::to_value(Response::from(story)).unwrap();
+        assert_eq!(value["story"]["content_rating"], 2);
+        assert_eq!(value["story"]["content_rating_text"], "Mature");
+    }
+
+    #[test]
+    fn words_formatted() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.words_formatted(), "275,949");
+        assert_eq!(story.words_formatted_with('.', 3), "275.949");
+        assert_eq!(story.words_formatted_with('\u{202f}', 3), "275\u{202f}949");
+        assert_eq!(story.words_formatted_with(' ', 4), "27 5949");
+        assert_eq!(story.words_formatted_with(',', 0), "275949");
+
+        story.words = 1_234_567;
+        assert_eq!(story.words_formatted(), "1,234,567");
+        story.words = 999;
+        assert_eq!(story.words_formatted(), "999");
+        story.words = 0;
+        assert_eq!(story.words_formatted(), "0");
+    }
+
+    #[test]
+    fn coded_error_response() {
+        let response = r#"{ "error": "Rate limit exceeded", "code": 429 }"#;
+        match parse_response(response).unwrap() {
+            Response::Error { message, code } => {
+                assert_eq!(message, "Rate limit exceeded");
+                assert_eq!(code, Some(429));
+            }
+            response => panic!("expected an error response, got: {response:?}"),
+        }
+
         match from_str(response).unwrap_err() {
-            StoryError::Json(_) => {}
-            err => panic!("expected a deserialization error, got: {err:?}"),
+            StoryError::Api { message, code } => {
+                assert_eq!(message, "Rate limit exceeded");
+                assert_eq!(code, Some(429));
+            }
+            err => panic!("expected an API error, got: {err:?}"),
+        }
+        #[cfg(feature = "cow")]
+        assert!(matches!(
+            cow::from_str_borrowed(response).unwrap_err(),
+            StoryError::Api {
+                code: Some(429),
+                ..
+            }
+        ));
+
+        for response in [
+            r#"{ "error": "Rate limit exceeded", "code": 429 }"#,
+            r#"{ "error": "Invalid story id" }"#,
+        ] {
+            let value: Value = serde_json::from_str(response).unwrap();
+            let serialized_value = serde_json::to_value(parse_response(response).unwrap()).unwrap();
+            assert_eq!(serialized_value, value);
         }
+
+        assert!(matches!(
+            parse_response(r#"{ "error": "Rate limit exceeded", "status": 429 }"#).unwrap(),
+            Response::Unknown(_)
+        ));
+        assert!(parse_response(r#"{ "error": "Rate limit exceeded", "code": "429" }"#).is_err());
     }
 
     #[test]
-    fn unpublished_error() {
-        let response = r#"{
-    "story": {
-        "author": {
-            "id": 1,
-            "name": "Author"
-        },
-        "chapter_count": 0,
-        "comments": 0,
-        "content_rating": 0,
-        "content_rating_text": "Everyone",
-        "date_modified": 0,
-        "description": "",
-        "dislikes": -1,
-        "id": 1,
-        "likes": -1,
-        "short_description": "",
-        "status": "Incomplete",
-        "title": "A story",
-        "total_views": 0,
-        "url": "https://www.fimfiction.net/story/1/a-story",
-        "views": 0,
-        "words": 0
+    fn is_oneshot() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert!(!story.is_oneshot());
+
+        story.truncate_chapters(1);
+        story.recompute_totals();
+        assert!(!story.is_oneshot());
+
+        story.status = StoryStatus::Complete;
+        assert!(story.is_oneshot());
     }
-}"#;
 
-        match from_str(response).unwrap_err() {
-            StoryError::Unpublished(_) => {}
-            err => panic!("expected an unpublished error, got: {err:?}"),
+    #[test]
+    fn deserialize_nested_response() {
+        let to_value = |story: Story| serde_json::to_value(Response::from(story)).unwrap();
+        let sample = to_value(from_str(RESPONSE_SAMPLE).unwrap());
+
+        let nested = format!(r#"{{ "data": {RESPONSE_SAMPLE} }}"#);
+        assert_eq!(to_value(from_str_nested(&nested).unwrap()), sample);
+        assert_eq!(to_value(from_str_nested(RESPONSE_SAMPLE).unwrap()), sample);
+        assert!(matches!(
+            from_str(&nested).unwrap_err(),
+            StoryError::UnexpectedShape(_)
+        ));
+
+        let nested_error = r#"{ "data": { "error": "Invalid story id" } }"#;
+        assert!(matches!(
+            from_str_nested(nested_error).unwrap_err(),
+            StoryError::InvalidId
+        ));
+
+        let extra_key = format!(r#"{{ "data": {RESPONSE_SAMPLE}, "meta": {{}} }}"#);
+        assert!(matches!(
+            from_str_nested(&extra_key).unwrap_err(),
+            StoryError::UnexpectedShape(_)
+        ));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn update_gaps() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        let gaps = story.update_gaps();
+        assert_eq!(gaps.len(), 39);
+        assert_eq!(gaps[0], chrono::Duration::seconds(2262383));
+        assert!(gaps.iter().all(|gap| *gap >= chrono::Duration::zero()));
+        assert_eq!(
+            story.longest_update_gap(),
+            Some(chrono::Duration::seconds(14019628))
+        );
+
+        story.truncate_chapters(1);
+        assert!(story.update_gaps().is_empty());
+        assert_eq!(story.longest_update_gap(), None);
+    }
+
+    #[test]
+    fn full_image_or_derived() {
+        let sample = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(sample.full_image_or_derived(), sample.full_image);
+
+        let response = edited_sample(|story| {
+            story.remove("full_image");
+        });
+        let story = from_str(&response).unwrap();
+        assert_eq!(story.full_image_or_derived(), sample.full_image);
+        #[cfg(feature = "derive-full-image")]
+        assert_eq!(story.full_image, sample.full_image);
+        #[cfg(not(feature = "derive-full-image"))]
+        assert_eq!(story.full_image, None);
+
+        let response = edited_sample(|story| {
+            story.remove("full_image");
+            story.insert("image".to_string(), "https://example.com/cover.png".into());
+        });
+        let story = from_str(&response).unwrap();
+        assert_eq!(story.full_image_or_derived(), None);
+        assert_eq!(story.full_image, None);
+
+        let mut value: Value = serde_json::from_str(RESPONSE_SAMPLE).unwrap();
+        value["story"]["full_image"] = Value::Null;
+        let story: Story = serde_json::from_value(value["story"].clone()).unwrap();
+        let cached: CachedStory =
+            serde_json::from_value(serde_json::json!({ "story": value["story"], "fetched_at": 0 }))
+                .unwrap();
+        #[cfg(feature = "derive-full-image")]
+        {
+            assert_eq!(story.full_image, sample.full_image);
+            assert_eq!(cached.story.full_image, sample.full_image);
+        }
+        #[cfg(not(feature = "derive-full-image"))]
+        {
+            assert_eq!(story.full_image, None);
+            assert_eq!(cached.story.full_image, None);
+        }
+    }
+
+    #[test]
+    fn estimated_chapters_remaining() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        // 275949 words in 40 chapters, about 6899 words each.
+        assert_eq!(story.estimated_chapters_remaining(300_000), Some(4));
+        assert_eq!(story.estimated_chapters_remaining(282_847), Some(1));
+        assert_eq!(story.estimated_chapters_remaining(100_000), Some(0));
+
+        story.status = StoryStatus::Complete;
+        assert_eq!(story.estimated_chapters_remaining(300_000), None);
+
+        story.status = StoryStatus::Incomplete;
+        story.chapters.clear();
+        assert_eq!(story.estimated_chapters_remaining(300_000), None);
+    }
+
+    #[test]
+    fn short_id() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        assert_eq!(story.short_id(), "970f");
+        assert_eq!(parse_short_id("970f"), Some(428991));
+        assert_eq!(parse_short_id("970F"), Some(428991));
+
+        for (id, short_id) in [(0, "0"), (35, "z"), (36, "10"), (Id::MAX, "1z141z3")] {
+            story.id = id;
+            assert_eq!(story.short_id(), short_id);
+            assert_eq!(parse_short_id(short_id), Some(id));
+        }
+
+        assert_eq!(parse_short_id("1z141z4"), None);
+        assert_eq!(parse_short_id(""), None);
+        assert_eq!(parse_short_id("+970f"), None);
+        assert_eq!(parse_short_id("97-0f"), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn modified_date_in() {
+        use chrono::{Datelike, FixedOffset, Timelike};
+
+        // 2022-12-15T16:41:54Z
+        let story = from_str(RESPONSE_SAMPLE).unwrap();
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+        let date = story.modified_date_in(&tokyo);
+        assert_eq!((date.day(), date.hour()), (16, 1));
+        assert_eq!(date, story.date_modified);
+
+        let honolulu = FixedOffset::west_opt(10 * 3600).unwrap();
+        assert_eq!(story.modified_date_in(&honolulu).hour(), 6);
+        assert_eq!(story.modified_date_in(&Utc).day(), 15);
+
+        // 2023-03-13T23:39:42Z
+        let chapter = &story.chapters[39];
+        assert_eq!(chapter.modified_date_in(&tokyo).day(), 14);
+        assert_eq!(chapter.modified_date_in(&Utc).day(), 13);
+    }
+
+    #[test]
+    fn adversarial_input_is_an_error() {
+        // Without chrono, a timestamp out of range of seconds is taken as milliseconds.
+        let edits: [(&str, Value, bool); 12] = [
+            (
+                "date_modified",
+                i64::MIN.into(),
+                cfg!(not(feature = "chrono")),
+            ),
+            ("date_modified", u64::MAX.into(), false),
+            ("date_modified", 1e308.into(), false),
+            ("content_rating", u64::MAX.into(), false),
+            ("content_rating", (-1).into(), false),
+            ("content_rating", 2.5.into(), false),
+            ("likes", u64::MAX.into(), false),
+            ("views", i64::MIN.into(), true),
+            ("words", (-1).into(), false),
+            ("status", "".into(), false),
+            ("tags", ",,,".into(), true),
+            ("author", serde_json::json!({ "id": -1 }), false),
+        ];
+        for (key, value, valid) in edits {
+            let response = edited_sample(|story| {
+                story.insert(key.to_string(), value.clone());
+            });
+            let result = from_str(&response);
+            assert_eq!(result.is_ok(), valid, "{key}: {value} gave {result:?}");
+        }
+
+        let views = edited_sample(|story| {
+            story.insert("views".to_string(), i64::MIN.into());
+        });
+        assert_eq!(from_str(&views).unwrap().views, 0);
+        let tags = edited_sample(|story| {
+            story.insert("tags".to_string(), ",,,".into());
+        });
+        assert!(from_str(&tags).unwrap().tags.is_empty());
+
+        let nested = "[".repeat(10_000);
+        assert!(from_str(&nested).is_err());
+        for input in [
+            "",
+            "{",
+            r#"{"story": "#,
+            r#"{"story": []}"#,
+            r#"{"error": 1}"#,
+            "\u{0}",
+        ] {
+            assert!(from_str(input).is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn from_str_raw() {
+        let response = format!("  {}\n", renamed_sample("views", "num_views"));
+        let raw = super::from_str_raw(&response).unwrap();
+        assert_eq!(raw.raw, response);
+        assert_eq!(raw.story.views, 10712);
+        assert_ne!(to_string(raw.story).unwrap(), response);
+
+        assert!(matches!(
+            super::from_str_raw(r#"{ "error": "Invalid story id" }"#).unwrap_err(),
+            StoryError::InvalidId
+        ));
+    }
+
+    #[test]
+    fn engagement_score() {
+        let mut story = from_str(RESPONSE_SAMPLE).unwrap();
+        // Approval of 0.98472 and interaction of 2957 / 178800.
+        assert_approx_eq!(story.engagement_score().unwrap(), 0.500629, 1e-6);
+
+        story.comments = 0;
+        story.total_views = 0;
+        assert_approx_eq!(story.engagement_score().unwrap(), 0.984719 / 2.0, 1e-6);
+
+        story.likes = Some(0);
+        story.dislikes = Some(0);
+        assert_eq!(story.engagement_score(), Some(0.0));
+
+        story.total_views = 1;
+        story.comments = 10;
+        assert_eq!(story.engagement_score(), Some(0.5));
+
+        story.dislikes = None;
+        assert_eq!(story.engagement_score(), None);
+    }
+
+    #[test]
+    fn default_story() {
+        let story = Story::default();
+        assert_eq!(story.id, 0);
+        assert_eq!(timestamp(&story.date_modified), 0);
+        assert_eq!(story.status, StoryStatus::Incomplete);
+        assert!(story.chapters.is_empty());
+
+        let value = serde_json::to_value(Response::from(story)).unwrap();
+        assert_eq!(value["story"]["content_rating"], 0);
+        assert_eq!(value["story"]["content_rating_text"], "Everyone");
+        assert_eq!(value["story"]["likes"], -1);
+
+        let story = Story {
+            id: 428991,
+            chapter_count: 1,
+            ..Default::default()
+        };
+        assert_eq!(from_str(&to_string(story).unwrap()).unwrap().id, 428991);
+    }
+
+    #[test]
+    fn story_error_into_io_error() {
+        fn read(input: &str) -> std::io::Result<Story> {
+            Ok(from_str(input)?)
+        }
+
+        for input in [
+            r#"{ "error": "Invalid story id" }"#,
+            "{",
+            r#"{ "error": "Oops" }"#,
+        ] {
+            let err = read(input).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            let source = err.get_ref().unwrap().downcast_ref::<StoryError>();
+            assert!(source.is_some(), "{err:?}");
+        }
+        assert!(matches!(
+            read(r#"{ "error": "Invalid story id" }"#)
+                .unwrap_err()
+                .into_inner()
+                .unwrap()
+                .downcast::<StoryError>()
+                .map(|err| *err),
+            Ok(StoryError::InvalidId)
+        ));
+        assert!(read(RESPONSE_SAMPLE).is_ok());
+    }
+
+    #[test]
+    fn deserialize_contradicting_rating() {
+        let response = edited_sample(|story| {
+            story.insert("content_rating_text".to_string(), Value::from("Mature"));
+            story.insert(
+                "rating".to_string(),
+                serde_json::json!({ "value": 1, "name": "Teen" }),
+            );
+        });
+        let story = from_str(&response).unwrap();
+        assert_eq!(story.content_rating, StoryRating::Everyone);
+
+        // Only the resolved rating is kept, so the disagreeing text doesn't round trip.
+        let value = serde_json::to_value(&story).unwrap();
+        assert_eq!(value["content_rating"], 0);
+        assert_eq!(value["content_rating_text"], "Everyone");
+        assert!(value.get("rating").is_none());
+
+        let response = edited_sample(|story| {
+            story.remove("content_rating");
+            story.insert("content_rating_text".to_string(), Value::from("Mature"));
+            story.insert(
+                "rating".to_string(),
+                serde_json::json!({ "value": 1, "name": "Teen" }),
+            );
+        });
+        let story = from_str(&response).unwrap();
+        assert_eq!(story.content_rating, StoryRating::Teen);
+
+        let response = edited_sample(|story| {
+            story.remove("content_rating");
+            story.insert("content_rating_text".to_string(), Value::from("Mature"));
+        });
+        let story = from_str(&response).unwrap();
+        assert_eq!(story.content_rating, StoryRating::Mature);
+    }
+
+    #[test]
+    fn deserialize_missing_rating() {
+        let response = edited_sample(|story| {
+            story.remove("content_rating");
+            story.remove("content_rating_text");
+        });
+        let error = from_str(&response).unwrap_err().to_string();
+        assert!(error.contains("missing field `content_rating`"), "{error}");
+    }
+
+    #[test]
+    fn story_error_position() {
+        let response = edited_sample(|story| {
+            story.insert("content_rating".to_string(), Value::from(3));
+        });
+        let error = parse_response(&response).unwrap_err();
+        let rating_column = response.find(r#""content_rating":3"#).unwrap();
+        assert_eq!(error.line(), 1);
+        assert!(error.column() > rating_column, "{error}");
+        assert!(error
+            .to_string()
+            .contains("expected an integer between 0 and 2"));
+
+        let extra_key = RESPONSE_SAMPLE.replacen('{', r#"{ "meta": {},"#, 1);
+        assert!(matches!(
+            parse_response(&extra_key).unwrap(),
+            Response::Unknown(_)
+        ));
+        let story_first = RESPONSE_SAMPLE.trim_end().strip_suffix('}').unwrap();
+        let story_first = format!(r#"{story_first}, "meta": {{}} }}"#);
+        match parse_response(&story_first).unwrap() {
+            Response::Unknown(value) => assert_eq!(value["story"]["id"], 428991),
+            response => panic!("expected an unknown response, got: {response:?}"),
         }
     }
 }
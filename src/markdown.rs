@@ -0,0 +1,83 @@
+use std::fmt::Write;
+
+use crate::Story;
+
+/// Escapes the characters that have a special meaning in Markdown inline text.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Wraps a URL in angle brackets so parentheses and spaces in it don't end the link.
+fn link_destination(url: &str) -> String {
+    format!("<{}>", url.replace('<', "%3C").replace('>', "%3E"))
+}
+
+impl Story {
+    /// A Markdown summary of the story, like for a README or an issue comment.
+    ///
+    /// Has the title linked to the story, its author, status and word count, the
+    /// [`short_description`](Story::short_description) as a blockquote and an ordered list of
+    /// the chapters linked to each of them.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+
+        let _ = writeln!(
+            markdown,
+            "**[{}]({})** by {}",
+            escape(&self.title),
+            link_destination(&self.url),
+            escape(self.author_or_unknown())
+        );
+        let _ = writeln!(markdown);
+        let _ = writeln!(markdown, "{} · {} words", self.status, self.words);
+
+        let short_description = self.short_description.trim();
+        if !short_description.is_empty() {
+            let _ = writeln!(markdown);
+            for line in short_description.lines() {
+                let _ = writeln!(markdown, "> {}", escape(line.trim_end()));
+            }
+        }
+
+        if !self.chapters.is_empty() {
+            let _ = writeln!(markdown);
+            for (number, chapter) in (1..).zip(&self.chapters) {
+                let _ = writeln!(
+                    markdown,
+                    "{number}. [{}]({})",
+                    escape(&chapter.title),
+                    link_destination(&chapter.link)
+                );
+            }
+        }
+
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_markdown() {
+        assert_eq!(escape("*Bold* [link](url)"), r"\*Bold\* \[link\](url)");
+        assert_eq!(escape("snake_case #1"), r"snake\_case \#1");
+    }
+
+    #[test]
+    fn link_destinations() {
+        assert_eq!(
+            link_destination("https://example.com/a (b)"),
+            "<https://example.com/a (b)>"
+        );
+        assert_eq!(link_destination("https://x/<y>"), "<https://x/%3Cy%3E>");
+    }
+}
@@ -0,0 +1,47 @@
+use std::fmt::Write;
+
+use crate::{
+    timestamp,
+    xml::{escape, rfc3339},
+    Story,
+};
+
+impl Story {
+    /// An Atom `<entry>` of the story for [OPDS](https://specs.opds.io/) catalogs.
+    ///
+    /// Includes the title, author, summary (the short description), last update and cover
+    /// links if any.
+    pub fn to_opds_entry(&self) -> String {
+        let mut entry = String::from("<entry>");
+        let _ = write!(
+            entry,
+            "<title>{}</title><id>{}</id><updated>{}</updated>\
+             <author><name>{}</name></author><summary>{}</summary>\
+             <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>",
+            escape(&self.title),
+            escape(&self.url),
+            rfc3339(timestamp(&self.date_modified)),
+            escape(&self.author.name),
+            escape(&self.short_description),
+            escape(&self.url),
+        );
+
+        if let Some(image) = &self.full_image {
+            let _ = write!(
+                entry,
+                "<link rel=\"http://opds-spec.org/image\" href=\"{}\"/>",
+                escape(image)
+            );
+        }
+        if let Some(image) = &self.image {
+            let _ = write!(
+                entry,
+                "<link rel=\"http://opds-spec.org/image/thumbnail\" href=\"{}\"/>",
+                escape(image)
+            );
+        }
+
+        entry.push_str("</entry>");
+        entry
+    }
+}
@@ -0,0 +1,53 @@
+use std::fmt::Write;
+
+use crate::{
+    timestamp,
+    xml::{escape, rfc3339},
+    Story,
+};
+
+impl Story {
+    /// A minimal OPF package document of the story, like the `metadata.opf` files used by
+    /// [Calibre](https://calibre-ebook.com/).
+    ///
+    /// Includes the title, author, description, last update and the name of each tag as a
+    /// subject.
+    pub fn to_opf(&self) -> String {
+        let mut opf = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"2.0\" ",
+            "unique-identifier=\"fimfiction_id\">\n",
+            "  <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\" ",
+            "xmlns:opf=\"http://www.idpf.org/2007/opf\">\n",
+        ));
+
+        let _ = writeln!(
+            opf,
+            "    <dc:identifier id=\"fimfiction_id\" opf:scheme=\"URL\">{}</dc:identifier>",
+            escape(&self.url)
+        );
+        let _ = writeln!(opf, "    <dc:title>{}</dc:title>", escape(&self.title));
+        let _ = writeln!(
+            opf,
+            "    <dc:creator opf:role=\"aut\">{}</dc:creator>",
+            escape(&self.author.name)
+        );
+        let _ = writeln!(
+            opf,
+            "    <dc:description>{}</dc:description>",
+            escape(&self.description)
+        );
+        let _ = writeln!(opf, "    <dc:publisher>Fimfiction</dc:publisher>");
+        let _ = writeln!(
+            opf,
+            "    <dc:date>{}</dc:date>",
+            rfc3339(timestamp(&self.date_modified))
+        );
+        for tag in &self.tags {
+            let _ = writeln!(opf, "    <dc:subject>{}</dc:subject>", escape(&tag.name));
+        }
+
+        opf.push_str("  </metadata>\n</package>\n");
+        opf
+    }
+}
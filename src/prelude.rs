@@ -0,0 +1,23 @@
+//! Re-exports of the commonly used types and functions of the crate.
+//!
+//! ```
+//! use fimfiction_api::prelude::*;
+//!
+//! # let response = &to_string(Story {
+//! #     id: 1,
+//! #     chapter_count: 1,
+//! #     status: StoryStatus::Complete,
+//! #     ..Story::default()
+//! # })?;
+//! let story: Story = from_str(response)?;
+//! assert_eq!(story.status, StoryStatus::Complete);
+//! assert_eq!(story.content_rating, StoryRating::Everyone);
+//! # Ok::<(), StoryError>(())
+//! ```
+
+#[cfg(feature = "ureq")]
+pub use crate::{fetch_story_ureq, fetch_story_ureq_with, FetchConfig, FetchError};
+pub use crate::{
+    from_str, parse_response, to_string, Author, Chapter, ChapterList, Id, Response, Story,
+    StoryError, StoryRating, StoryStatus, Tag, TagKind,
+};
@@ -0,0 +1,144 @@
+//! [`proptest`](https://docs.rs/proptest/1) strategies generating valid stories.
+//!
+//! ```
+//! use fimfiction_api::proptest::story_strategy;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn consistent_chapter_count(story in story_strategy()) {
+//!         prop_assert_eq!(story.chapter_count, story.chapters.len() as u64);
+//!     }
+//! }
+//! # consistent_chapter_count();
+//! ```
+
+use ::proptest::{collection::vec, option, prelude::*};
+
+use crate::{date, Author, Chapter, Id, Story, StoryRating, StoryStatus};
+
+/// Strategy for a valid [`StoryRating`].
+pub fn rating_strategy() -> impl Strategy<Value = StoryRating> {
+    prop_oneof![
+        Just(StoryRating::Everyone),
+        Just(StoryRating::Teen),
+        Just(StoryRating::Mature),
+    ]
+}
+
+/// Strategy for a valid [`StoryStatus`].
+pub fn status_strategy() -> impl Strategy<Value = StoryStatus> {
+    prop_oneof![
+        Just(StoryStatus::Complete),
+        Just(StoryStatus::Incomplete),
+        Just(StoryStatus::Hiatus),
+        Just(StoryStatus::Cancelled),
+    ]
+}
+
+/// Strategy for an [`Author`] with a non-empty name.
+pub fn author_strategy() -> impl Strategy<Value = Author> {
+    (1..=Id::MAX, "[A-Za-z0-9 ]{1,32}").prop_map(|(id, name)| Author { id, name })
+}
+
+fn date_strategy() -> impl Strategy<Value = crate::Date> {
+    (0..=date::MAX_SECONDS).prop_map(date::from_seconds)
+}
+
+/// Strategy for a [`Chapter`] of up to 20000 words.
+///
+/// Its link doesn't point to any story, [`story_strategy()`] sets it to point to the story it
+/// generates.
+pub fn chapter_strategy() -> impl Strategy<Value = Chapter> {
+    (
+        1..=Id::MAX,
+        "[A-Za-z0-9 ]{1,64}",
+        1..=20_000u64,
+        any::<u32>(),
+        date_strategy(),
+    )
+        .prop_map(|(id, title, words, views, date_modified)| Chapter {
+            id,
+            link: format!("https://www.fimfiction.net/chapter/{id}"),
+            title,
+            words,
+            views,
+            date_modified,
+        })
+}
+
+/// Strategy for a valid published [`Story`].
+///
+/// It has between 1 and 20 chapters, with [`chapter_count`](Story::chapter_count) and
+/// [`words`](Story::words) consistent with them.
+pub fn story_strategy() -> impl Strategy<Value = Story> {
+    let metadata = (
+        1..=Id::MAX,
+        "[A-Za-z0-9 ]{1,64}",
+        ".{0,128}",
+        ".{0,512}",
+        date_strategy(),
+        any::<bool>(),
+    );
+    let metrics = (
+        any::<u32>(),
+        any::<u32>(),
+        any::<u32>(),
+        option::of(any::<u32>()),
+        option::of(any::<u32>()),
+    );
+
+    (
+        metadata,
+        metrics,
+        author_strategy(),
+        status_strategy(),
+        rating_strategy(),
+        vec(chapter_strategy(), 1..=20),
+    )
+        .prop_map(
+            |(
+                (id, title, short_description, description, date_modified, has_cover),
+                (views, total_views, comments, likes, dislikes),
+                author,
+                status,
+                content_rating,
+                mut chapters,
+            )| {
+                let url = format!("https://www.fimfiction.net/story/{id}");
+                for (index, chapter) in chapters.iter_mut().enumerate() {
+                    chapter.link = format!("{url}/{}", index + 1);
+                }
+                let cover = |size: &str| {
+                    has_cover.then(|| format!("https://cdn-img.fimfiction.net/story/{id}-{size}"))
+                };
+
+                let mut story = Story {
+                    id,
+                    title,
+                    image: cover("medium"),
+                    full_image: cover("full"),
+                    url,
+                    short_description,
+                    description,
+                    date_modified,
+                    views,
+                    total_views,
+                    words: 0,
+                    chapter_count: 0,
+                    comments,
+                    author,
+                    status,
+                    publication_state: None,
+                    language: None,
+                    content_rating,
+                    likes,
+                    dislikes,
+                    tags: Vec::new(),
+                    groups: Vec::new(),
+                    chapters: chapters.into(),
+                };
+                story.recompute_totals();
+                story
+            },
+        )
+}
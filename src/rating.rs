@@ -1,3 +1,5 @@
+//! The [`StoryRating`] of a story and [`serde`] modules for its text representation, to be used
+//! with `#[serde(with = "...")]`.
 use std::fmt;
 
 use serde::de::{self, Unexpected, Visitor};
@@ -22,6 +24,38 @@ pub enum StoryRating {
     Mature,
 }
 
+impl StoryRating {
+    /// Value of the `content_rating` query parameter of Fimfiction's story search that filters
+    /// by this rating, same as the numeric rating given by the API.
+    ///
+    /// ```
+    /// # use fimfiction_api::StoryRating;
+    /// assert_eq!(StoryRating::Everyone.filter_param(), "0");
+    /// assert_eq!(StoryRating::Teen.filter_param(), "1");
+    /// assert_eq!(StoryRating::Mature.filter_param(), "2");
+    /// ```
+    pub fn filter_param(&self) -> &'static str {
+        match self {
+            StoryRating::Everyone => "0",
+            StoryRating::Teen => "1",
+            StoryRating::Mature => "2",
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for StoryRating {
+    fn arbitrary(unstructured: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        unstructured
+            .choose(&[
+                StoryRating::Everyone,
+                StoryRating::Teen,
+                StoryRating::Mature,
+            ])
+            .copied()
+    }
+}
+
 impl PartialEq for StoryRating {
     fn eq(&self, other: &Self) -> bool {
         (*self as u8) == (*other as u8)
@@ -47,13 +81,29 @@ impl Serialize for StoryRating {
     }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for StoryRating {
+    fn schema_name() -> String {
+        "StoryRating".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::Integer.into()),
+            enum_values: Some(vec![0.into(), 1.into(), 2.into()]),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 struct RatingVisitor;
 
 impl<'de> Visitor<'de> for RatingVisitor {
     type Value = StoryRating;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("an integer between 0 and 3")
+        formatter.write_str("an integer between 0 and 2")
     }
 
     fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
@@ -67,6 +117,17 @@ impl<'de> Visitor<'de> for RatingVisitor {
             _ => Err(E::invalid_value(Unexpected::Unsigned(value), &self)),
         }
     }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if value.fract() == 0.0 && (0.0..=2.0).contains(&value) {
+            self.visit_u64(value as u64)
+        } else {
+            Err(E::invalid_value(Unexpected::Float(value), &self))
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for StoryRating {
@@ -78,7 +139,8 @@ impl<'de> Deserialize<'de> for StoryRating {
     }
 }
 
-pub(crate) mod serde_text {
+/// (De)serialization of a [`StoryRating`] as its title-cased text, like `"Everyone"`.
+pub mod serde_text {
     use super::*;
 
     struct RatingTextVisitor;
@@ -103,6 +165,7 @@ pub(crate) mod serde_text {
         }
     }
 
+    /// Deserializes a [`StoryRating`] from its title-cased text.
     pub fn deserialize<'de, D>(deserializer: D) -> Result<StoryRating, D::Error>
     where
         D: Deserializer<'de>,
@@ -110,6 +173,7 @@ pub(crate) mod serde_text {
         deserializer.deserialize_any(RatingTextVisitor)
     }
 
+    /// Serializes a [`StoryRating`] as its title-cased text.
     pub fn serialize<S>(rating: &StoryRating, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -118,6 +182,93 @@ pub(crate) mod serde_text {
     }
 }
 
+/// (De)serialization of a [`StoryRating`] as its lowercase text, like `"everyone"`.
+///
+/// Deserialization is case-insensitive.
+pub mod serde_text_lower {
+    use super::*;
+
+    struct RatingTextVisitor;
+
+    impl<'de> Visitor<'de> for RatingTextVisitor {
+        type Value = StoryRating;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("one \"everyone\", \"teen\" or \"mature\" in any casing")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            match value.to_ascii_lowercase().as_str() {
+                "everyone" => Ok(StoryRating::Everyone),
+                "teen" => Ok(StoryRating::Teen),
+                "mature" => Ok(StoryRating::Mature),
+                _ => Err(E::invalid_value(Unexpected::Str(value), &self)),
+            }
+        }
+    }
+
+    /// Deserializes a [`StoryRating`] from its text in any casing.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<StoryRating, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(RatingTextVisitor)
+    }
+
+    /// Serializes a [`StoryRating`] as its lowercase text.
+    pub fn serialize<S>(rating: &StoryRating, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&rating.to_string().to_lowercase())
+    }
+}
+
+/// A [`StoryRating`] (de)serialized as its title-cased text, like the API's
+/// `content_rating_text`.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct RatingText(#[serde(with = "serde_text")] pub(crate) StoryRating);
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for RatingText {
+    fn schema_name() -> String {
+        "StoryRatingText".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            enum_values: Some(vec!["Everyone".into(), "Teen".into(), "Mature".into()]),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// The nested `rating: {value, name}` object some responses give in place of the flat rating
+/// fields, of which only `value` is used.
+#[derive(Deserialize)]
+pub(crate) struct NestedRating {
+    value: StoryRating,
+}
+
+/// Resolves the rating out of the different fields the API can give for it.
+///
+/// `content_rating` takes precedence over the nested `rating`, which takes precedence over
+/// `content_rating_text`, without checking that they agree. Returns `None` if all are missing.
+pub(crate) fn from_fields(
+    content_rating: Option<StoryRating>,
+    rating: Option<NestedRating>,
+    content_rating_text: Option<RatingText>,
+) -> Option<StoryRating> {
+    content_rating
+        .or(rating.map(|rating| rating.value))
+        .or(content_rating_text.map(|RatingText(rating)| rating))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -156,10 +307,71 @@ mod test {
         assert_deserialize!(2 => Mature);
     }
 
+    #[test]
+    fn deserialize_float() {
+        assert_deserialize!(0.0 => Everyone);
+        assert_deserialize!(1.0 => Teen);
+        assert_deserialize!(2.0 => Mature);
+
+        for value in [1.5, -1.0, 3.0] {
+            serde_json::from_value::<Test>(json!({ "content_rating": value }))
+                .expect_err("only whole floats in range should be valid");
+        }
+    }
+
     #[test]
     fn serialize() {
         assert_serialize!(Everyone => 0);
         assert_serialize!(Teen => 1);
         assert_serialize!(Mature => 2);
     }
+
+    #[test]
+    fn from_fields() {
+        let text = || Some(RatingText(StoryRating::Teen));
+        let nested = || {
+            Some(NestedRating {
+                value: StoryRating::Everyone,
+            })
+        };
+
+        assert_eq!(
+            super::from_fields(Some(StoryRating::Mature), nested(), text()),
+            Some(StoryRating::Mature)
+        );
+        assert_eq!(
+            super::from_fields(None, nested(), text()),
+            Some(StoryRating::Everyone)
+        );
+        assert_eq!(
+            super::from_fields(None, None, text()),
+            Some(StoryRating::Teen)
+        );
+        assert_eq!(super::from_fields(None, None, None), None);
+    }
+
+    #[derive(Deserialize, Serialize, Debug)]
+    struct Lower {
+        #[serde(with = "serde_text_lower")]
+        content_rating_text: StoryRating,
+    }
+
+    #[test]
+    fn serde_text_lower() {
+        for (text, variant) in [
+            ("everyone", StoryRating::Everyone),
+            ("Teen", StoryRating::Teen),
+            ("MATURE", StoryRating::Mature),
+        ] {
+            let lower: Lower = serde_json::from_value(json!({ "content_rating_text": text }))
+                .expect("StoryRating should be deserializable");
+            assert_eq!(lower.content_rating_text, variant);
+
+            let value = serde_json::to_value(lower).expect("StoryRating should be serializable");
+            assert_eq!(value, json!({ "content_rating_text": text.to_lowercase() }));
+        }
+
+        serde_json::from_value::<Lower>(json!({ "content_rating_text": "adult" }))
+            .expect_err("\"adult\" is not a valid StoryRating");
+    }
 }
@@ -0,0 +1,31 @@
+use crate::{from_str, Story, StoryError};
+
+/// A [`Story`] along with the response it was deserialized from, given by [`from_str_raw()`].
+///
+/// Serializing the story again can reorder keys or change how numbers are written, keeping the
+/// response allows writing back the exact same bytes, like in a cache.
+#[derive(Debug, Clone)]
+pub struct RawStory {
+    /// The response, as given.
+    pub raw: String,
+    /// The story deserialized from [`raw`](RawStory::raw).
+    pub story: Story,
+}
+
+impl From<RawStory> for Story {
+    fn from(raw: RawStory) -> Self {
+        raw.story
+    }
+}
+
+/// Deserialize an instance of [`Story`] from an API response String, keeping a copy of the
+/// response.
+///
+/// # Errors
+/// Same as [`from_str()`].
+pub fn from_str_raw(input: &str) -> Result<RawStory, StoryError> {
+    Ok(RawStory {
+        story: from_str(input)?,
+        raw: input.to_string(),
+    })
+}
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::rating::{self, NestedRating, RatingText};
+use crate::{
+    date, tag, vote, Author, ChapterList, Date, GroupLink, Id, PublicationState, Story,
+    StoryRating, StoryStatus, Tag,
+};
+
+/// The form a [`Story`] takes in API responses, which it is (de)serialized through.
+///
+/// The rating comes as up to three separate fields here, which are resolved into the single
+/// [`content_rating`](Story::content_rating) of the story.
+#[derive(Deserialize, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct StoryRepr {
+    id: Id,
+    title: String,
+    url: String,
+    #[serde(alias = "shortDescription")]
+    short_description: String,
+    description: String,
+    #[serde(with = "date", alias = "dateModified")]
+    #[cfg_attr(feature = "schemars", schemars(with = "i64"))]
+    date_modified: Date,
+    /// Only `None` when left out of a compact serialization, `Some(None)` for `null`.
+    #[serde(
+        default,
+        deserialize_with = "some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    image: Option<Option<String>>,
+    /// Same as `image`.
+    #[serde(
+        default,
+        alias = "fullImage",
+        deserialize_with = "some",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<String>"))]
+    full_image: Option<Option<String>>,
+    #[serde(with = "vote::or_zero", alias = "num_views")]
+    #[cfg_attr(feature = "schemars", schemars(with = "u32"))]
+    views: u32,
+    #[serde(
+        with = "vote::or_zero",
+        alias = "total_num_views",
+        alias = "totalViews"
+    )]
+    #[cfg_attr(feature = "schemars", schemars(with = "u32"))]
+    total_views: u32,
+    words: u64,
+    #[serde(
+        alias = "num_chapters",
+        alias = "number_of_chapters",
+        alias = "chapterCount"
+    )]
+    chapter_count: u64,
+    #[serde(default, alias = "num_comments")]
+    comments: u32,
+    #[serde(default)]
+    author: Author,
+    status: StoryStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    publication_state: Option<PublicationState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    #[serde(alias = "contentRatingText", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "RatingText"))]
+    content_rating_text: Option<RatingText>,
+    #[serde(alias = "contentRating", skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "schemars", schemars(with = "StoryRating"))]
+    content_rating: Option<StoryRating>,
+    #[serde(default, skip_serializing)]
+    #[cfg_attr(feature = "schemars", schemars(skip))]
+    rating: Option<NestedRating>,
+    #[serde(with = "vote", alias = "num_likes")]
+    #[cfg_attr(feature = "schemars", schemars(with = "i64"))]
+    likes: Option<u32>,
+    #[serde(with = "vote", alias = "num_dislikes")]
+    #[cfg_attr(feature = "schemars", schemars(with = "i64"))]
+    dislikes: Option<u32>,
+    #[serde(
+        default,
+        deserialize_with = "tag::deserialize_tags",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    tags: Vec<Tag>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    groups: Vec<GroupLink>,
+    #[serde(default)]
+    chapters: ChapterList,
+}
+
+impl StoryRepr {
+    /// Same as [`StoryRepr::from()`], but leaving out the optional fields that are `None` instead
+    /// of serializing them as `null`.
+    pub(crate) fn compact(story: Story) -> Self {
+        let mut repr = StoryRepr::from(story);
+        repr.image = repr.image.flatten().map(Some);
+        repr.full_image = repr.full_image.flatten().map(Some);
+        repr
+    }
+}
+
+/// Deserializes a value that can be `null` as `Some`, so that `null` and a missing key can be told
+/// apart.
+fn some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+impl TryFrom<StoryRepr> for Story {
+    type Error = &'static str;
+
+    fn try_from(repr: StoryRepr) -> Result<Self, Self::Error> {
+        let content_rating =
+            rating::from_fields(repr.content_rating, repr.rating, repr.content_rating_text)
+                .ok_or("missing field `content_rating`")?;
+
+        let story = Story {
+            id: repr.id,
+            title: repr.title,
+            url: repr.url,
+            short_description: repr.short_description,
+            description: repr.description,
+            date_modified: repr.date_modified,
+            image: repr.image.flatten(),
+            full_image: repr.full_image.flatten(),
+            views: repr.views,
+            total_views: repr.total_views,
+            words: repr.words,
+            chapter_count: repr.chapter_count,
+            comments: repr.comments,
+            author: repr.author,
+            status: repr.status,
+            publication_state: repr.publication_state,
+            language: repr.language,
+            content_rating,
+            likes: repr.likes,
+            dislikes: repr.dislikes,
+            tags: repr.tags,
+            groups: repr.groups,
+            chapters: repr.chapters,
+        };
+        #[cfg(feature = "derive-full-image")]
+        let story = Story {
+            full_image: story.full_image_or_derived(),
+            ..story
+        };
+        Ok(story)
+    }
+}
+
+impl From<Story> for StoryRepr {
+    fn from(story: Story) -> Self {
+        StoryRepr {
+            id: story.id,
+            title: story.title,
+            url: story.url,
+            short_description: story.short_description,
+            description: story.description,
+            date_modified: story.date_modified,
+            image: Some(story.image),
+            full_image: Some(story.full_image),
+            views: story.views,
+            total_views: story.total_views,
+            words: story.words,
+            chapter_count: story.chapter_count,
+            comments: story.comments,
+            author: story.author,
+            status: story.status,
+            publication_state: story.publication_state,
+            language: story.language,
+            content_rating_text: Some(RatingText(story.content_rating)),
+            content_rating: Some(story.content_rating),
+            rating: None,
+            likes: story.likes,
+            dislikes: story.dislikes,
+            tags: story.tags,
+            groups: story.groups,
+            chapters: story.chapters,
+        }
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Story {
+    fn schema_name() -> String {
+        "Story".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        StoryRepr::json_schema(gen)
+    }
+}
@@ -0,0 +1,68 @@
+use crate::Story;
+
+/// Removes the BBCode tags of `text`, like `[b]` or `[url=...]`, keeping their content.
+///
+/// Brackets that don't open a tag, like in `[citation needed]`, are kept.
+fn strip_markup(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        stripped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let tag_len = rest.find(']').filter(|&end| {
+            let tag = &rest[1..end];
+            let name = tag.strip_prefix('/').unwrap_or(tag);
+            let name = name.split_once('=').map_or(name, |(name, _)| name);
+            !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric())
+        });
+        match tag_len {
+            Some(end) => rest = &rest[end + 1..],
+            None => {
+                stripped.push('[');
+                rest = &rest[1..];
+            }
+        }
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+impl Story {
+    /// Whether the [`title`](Story::title), [`short_description`](Story::short_description) or
+    /// [`description`](Story::description) contain `query`, ignoring case and BBCode tags.
+    ///
+    /// An empty `query` matches every story.
+    pub fn matches_query(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [&self.title, &self.short_description, &self.description]
+            .into_iter()
+            .any(|text| strip_markup(text).to_lowercase().contains(&query))
+    }
+}
+
+/// The `stories` that [match](Story::matches_query) `query`, in the same order.
+pub fn search<'a>(stories: &'a [Story], query: &str) -> Vec<&'a Story> {
+    stories
+        .iter()
+        .filter(|story| story.matches_query(query))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strip_bbcode() {
+        assert_eq!(strip_markup("[b]Bold[/b] text"), "Bold text");
+        assert_eq!(
+            strip_markup("[url=https://www.fimfiction.net/]Fimfiction[/url]"),
+            "Fimfiction"
+        );
+        assert_eq!(
+            strip_markup("[citation needed] [] [ unclosed"),
+            "[citation needed] [] [ unclosed"
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use std::cmp::Reverse;
+
+use crate::{timestamp, Id, Story};
+
+/// The orderings [`Story::sort_key()`] can sort stories by, like the ones of Fimfiction's
+/// story search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortBy {
+    /// Most recently updated first.
+    Updated,
+    /// Most viewed first, by [`views`](Story::views).
+    Views,
+    /// Most viewed first, by [`total_views`](Story::total_views).
+    TotalViews,
+    /// Best rated first, by [`net_votes`](Story::net_votes) with disabled votes last.
+    Rating,
+    /// Longest first.
+    Words,
+}
+
+/// Key to sort stories by a [`SortBy`] ordering, given by [`Story::sort_key()`].
+///
+/// Stories that are equal on the ordering are sorted by ascending ID, so that sorts are
+/// deterministic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortKey {
+    value: Reverse<i64>,
+    id: Id,
+}
+
+impl Story {
+    /// Key to sort stories by the given ordering.
+    ///
+    /// ```no_run
+    /// # use fimfiction_api::{SortBy, Story};
+    /// # let mut stories: Vec<Story> = Vec::new();
+    /// stories.sort_by_key(|story| story.sort_key(SortBy::Views));
+    /// ```
+    pub fn sort_key(&self, by: SortBy) -> SortKey {
+        let value = match by {
+            SortBy::Updated => timestamp(&self.date_modified),
+            SortBy::Views => self.views.into(),
+            SortBy::TotalViews => self.total_views.into(),
+            SortBy::Rating => self.net_votes().unwrap_or(i64::MIN),
+            SortBy::Words => i64::try_from(self.words).unwrap_or(i64::MAX),
+        };
+
+        SortKey {
+            value: Reverse(value),
+            id: self.id,
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Storage of the scalar fields of a [`Story`] in SQLite with [`rusqlite`].
+//!
+//! ```
+//! # use fimfiction_api::{sqlite::STORY_COLUMNS, Story};
+//! # fn store(connection: &rusqlite::Connection, story: &Story) -> rusqlite::Result<Story> {
+//! let placeholders = vec!["?"; STORY_COLUMNS.len()].join(", ");
+//! let mut insert = connection.prepare(&format!(
+//!     "INSERT INTO stories ({}) VALUES ({placeholders})",
+//!     STORY_COLUMNS.join(", ")
+//! ))?;
+//! story.bind_to(&mut insert)?;
+//! insert.raw_execute()?;
+//!
+//! connection.query_row(
+//!     &format!("SELECT {} FROM stories WHERE id = ?", STORY_COLUMNS.join(", ")),
+//!     [story.id],
+//!     Story::from_row,
+//! )
+//! # }
+//! ```
+
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::{Row, Statement};
+use serde::de::{value::StrDeserializer, IntoDeserializer};
+use serde::Deserialize;
+
+use crate::{timestamp, Author, Date, PublicationState, Story, StoryRating, StoryStatus};
+
+/// Columns the scalar fields of a [`Story`] are mapped to, in the order used by
+/// [`Story::bind_to()`] and [`Story::from_row()`].
+///
+/// Named like the fields, except for the [`author`](Story::author) which is split into
+/// `author_id` and `author_name`.
+pub const STORY_COLUMNS: &[&str] = &[
+    "id",
+    "title",
+    "url",
+    "short_description",
+    "description",
+    "date_modified",
+    "image",
+    "full_image",
+    "views",
+    "total_views",
+    "words",
+    "chapter_count",
+    "comments",
+    "author_id",
+    "author_name",
+    "status",
+    "publication_state",
+    "language",
+    "content_rating",
+    "likes",
+    "dislikes",
+];
+
+/// Stored as its text, like `On Hiatus`.
+impl ToSql for StoryStatus {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+impl FromSql for StoryStatus {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text: StrDeserializer<serde::de::value::Error> = value.as_str()?.into_deserializer();
+        StoryStatus::deserialize(text).map_err(|err| FromSqlError::Other(Box::new(err)))
+    }
+}
+
+/// Stored as its lowercase name, like `published`.
+impl ToSql for PublicationState {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(match self {
+            PublicationState::Published => "published",
+            PublicationState::Unpublished => "unpublished",
+            PublicationState::Pending => "pending",
+        }))
+    }
+}
+
+impl FromSql for PublicationState {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text: StrDeserializer<serde::de::value::Error> = value.as_str()?.into_deserializer();
+        PublicationState::deserialize(text).map_err(|err| FromSqlError::Other(Box::new(err)))
+    }
+}
+
+/// Stored as its number, like in the `content_rating` field of the API.
+impl ToSql for StoryRating {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(*self as u8))
+    }
+}
+
+impl FromSql for StoryRating {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value.as_i64()? {
+            0 => Ok(StoryRating::Everyone),
+            1 => Ok(StoryRating::Teen),
+            2 => Ok(StoryRating::Mature),
+            value => Err(FromSqlError::OutOfRange(value)),
+        }
+    }
+}
+
+/// A `u64` as an SQLite integer, which is signed.
+fn to_integer(value: u64) -> rusqlite::Result<i64> {
+    i64::try_from(value).map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))
+}
+
+/// A `u64` from the SQLite integer of the column at `index`.
+fn get_u64(row: &Row, index: usize) -> rusqlite::Result<u64> {
+    let value: i64 = row.get(index)?;
+    u64::try_from(value).map_err(|_| {
+        rusqlite::Error::FromSqlConversionFailure(
+            index,
+            rusqlite::types::Type::Integer,
+            Box::new(FromSqlError::OutOfRange(value)),
+        )
+    })
+}
+
+/// A [`Date`] from the timestamp of the column at `index`.
+fn get_date(row: &Row, index: usize) -> rusqlite::Result<Date> {
+    let seconds: i64 = row.get(index)?;
+    #[cfg(not(feature = "chrono"))]
+    return Ok(seconds);
+    #[cfg(feature = "chrono")]
+    {
+        use chrono::TimeZone;
+
+        chrono::Utc
+            .timestamp_opt(seconds, 0)
+            .single()
+            .ok_or_else(|| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    index,
+                    rusqlite::types::Type::Integer,
+                    Box::new(FromSqlError::OutOfRange(seconds)),
+                )
+            })
+    }
+}
+
+impl Story {
+    /// Binds the scalar fields of the story to the parameters of `statement`, in the order of
+    /// [`STORY_COLUMNS`].
+    ///
+    /// The statement can then be run with [`Statement::raw_execute()`].
+    pub fn bind_to(&self, statement: &mut Statement) -> rusqlite::Result<()> {
+        let values: [&dyn ToSql; 21] = [
+            &self.id,
+            &self.title,
+            &self.url,
+            &self.short_description,
+            &self.description,
+            &timestamp(&self.date_modified),
+            &self.image,
+            &self.full_image,
+            &self.views,
+            &self.total_views,
+            &to_integer(self.words)?,
+            &to_integer(self.chapter_count)?,
+            &self.comments,
+            &self.author.id,
+            &self.author.name,
+            &self.status,
+            &self.publication_state,
+            &self.language,
+            &self.content_rating,
+            &self.likes,
+            &self.dislikes,
+        ];
+        for (index, value) in values.into_iter().enumerate() {
+            statement.raw_bind_parameter(index + 1, value)?;
+        }
+        Ok(())
+    }
+
+    /// A story from a row with the [`STORY_COLUMNS`], in order.
+    ///
+    /// Its chapters, tags and groups are left empty.
+    pub fn from_row(row: &Row) -> rusqlite::Result<Story> {
+        Ok(Story {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            url: row.get(2)?,
+            short_description: row.get(3)?,
+            description: row.get(4)?,
+            date_modified: get_date(row, 5)?,
+            image: row.get(6)?,
+            full_image: row.get(7)?,
+            views: row.get(8)?,
+            total_views: row.get(9)?,
+            words: get_u64(row, 10)?,
+            chapter_count: get_u64(row, 11)?,
+            comments: row.get(12)?,
+            author: Author {
+                id: row.get(13)?,
+                name: row.get(14)?,
+            },
+            status: row.get(15)?,
+            publication_state: row.get(16)?,
+            language: row.get(17)?,
+            content_rating: row.get(18)?,
+            likes: row.get(19)?,
+            dislikes: row.get(20)?,
+            tags: Vec::new(),
+            groups: Vec::new(),
+            chapters: Default::default(),
+        })
+    }
+}
@@ -25,12 +25,55 @@ pub enum StoryStatus {
     Cancelled,
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for StoryStatus {
+    fn arbitrary(unstructured: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        unstructured
+            .choose(&[
+                StoryStatus::Complete,
+                StoryStatus::Incomplete,
+                StoryStatus::Hiatus,
+                StoryStatus::Cancelled,
+            ])
+            .copied()
+    }
+}
+
 impl PartialEq for StoryStatus {
     fn eq(&self, other: &Self) -> bool {
         (*self as u8) == (*other as u8)
     }
 }
 
+impl StoryStatus {
+    /// Whether a story going from this status to `next` is plausible, like to warn about a
+    /// suspicious change between two snapshots of a story. It is advisory, the API doesn't
+    /// enforce any transition.
+    ///
+    /// Staying on the same status is always plausible, otherwise:
+    ///
+    /// | From \ To     | Complete | Incomplete | On Hiatus | Cancelled |
+    /// |---------------|:--------:|:----------:|:---------:|:---------:|
+    /// | **Complete**  |          |     no     |    no     |    no     |
+    /// | **Incomplete**|   yes    |            |    yes    |    yes    |
+    /// | **On Hiatus** |   yes    |    yes     |           |    yes    |
+    /// | **Cancelled** |   yes    |    yes     |    no     |           |
+    ///
+    /// A complete story isn't expected to go back, while a cancelled one can be picked up again
+    /// or wrapped up, but not paused.
+    pub fn can_transition_to(&self, next: StoryStatus) -> bool {
+        use StoryStatus::*;
+
+        match (self, next) {
+            (current, next) if *current == next => true,
+            (Complete, _) => false,
+            (Incomplete | Hiatus, _) => true,
+            (Cancelled, Hiatus) => false,
+            (Cancelled, _) => true,
+        }
+    }
+}
+
 impl fmt::Display for StoryStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -51,13 +94,34 @@ impl Serialize for StoryStatus {
     }
 }
 
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for StoryStatus {
+    fn schema_name() -> String {
+        "StoryStatus".to_string()
+    }
+
+    fn json_schema(_gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            enum_values: Some(vec![
+                "Complete".into(),
+                "Incomplete".into(),
+                "On Hiatus".into(),
+                "Cancelled".into(),
+            ]),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 struct StatusVisitor;
 
 impl<'de> Visitor<'de> for StatusVisitor {
     type Value = StoryStatus;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("a valid status string or an integer between 0 and 3")
+        formatter.write_str("one \"Complete\", \"Incomplete\", \"On Hiatus\" or \"Cancelled\"")
     }
 
     fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
@@ -90,6 +154,20 @@ impl<'de> Deserialize<'de> for StoryStatus {
     }
 }
 
+/// The publication states a [`Story`](crate::Story) can be in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum PublicationState {
+    /// A story visible to everyone.
+    Published,
+    /// A draft only visible to its author.
+    Unpublished,
+    /// A story submitted for publication and waiting for approval.
+    Pending,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -129,6 +207,25 @@ mod test {
         assert_deserialize!("Cancelled" => Cancelled);
     }
 
+    #[test]
+    fn transitions() {
+        use StoryStatus::*;
+
+        let statuses = [Complete, Incomplete, Hiatus, Cancelled];
+        for status in statuses {
+            assert!(status.can_transition_to(status));
+            assert!(Incomplete.can_transition_to(status));
+            assert!(Hiatus.can_transition_to(status));
+        }
+
+        assert!(!Complete.can_transition_to(Incomplete));
+        assert!(!Complete.can_transition_to(Hiatus));
+        assert!(!Complete.can_transition_to(Cancelled));
+        assert!(Cancelled.can_transition_to(Incomplete));
+        assert!(Cancelled.can_transition_to(Complete));
+        assert!(!Cancelled.can_transition_to(Hiatus));
+    }
+
     #[test]
     fn serialize() {
         assert_serialize!(Complete => "Complete");
@@ -0,0 +1,151 @@
+use std::fmt;
+
+use serde::de::{self, value::SeqAccessDeserializer, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The different kinds of tags a [`Story`](crate::Story) can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TagKind {
+    /// A character appearing in the story.
+    Character,
+    /// A genre of the story, like `Comedy` or `Adventure`.
+    Genre,
+    /// A content tag, like `Alternate Universe`.
+    Content,
+    /// A content warning, like `Gore`.
+    Warning,
+    /// The series the story belongs to, like `My Little Pony: Friendship is Magic`.
+    Series,
+    /// The rating of the story.
+    Rating,
+    /// A kind of tag without its own variant, serialized as `other` whatever it was given as.
+    #[serde(other)]
+    Other,
+}
+
+/// A tag of a [`Story`](crate::Story).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Tag {
+    /// Name of the tag.
+    pub name: String,
+    /// Kind of tag.
+    #[serde(rename = "type")]
+    pub kind: TagKind,
+}
+
+struct TagsVisitor;
+
+impl<'de> Visitor<'de> for TagsVisitor {
+    type Value = Vec<Tag>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of tags or a comma-separated string of tag names")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(value
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| Tag {
+                name: name.to_string(),
+                kind: TagKind::Other,
+            })
+            .collect())
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        Vec::deserialize(SeqAccessDeserializer::new(seq))
+    }
+}
+
+/// Deserializes tags from either an array of tags or a comma-separated string of tag names, in
+/// which case their kind is [`TagKind::Other`].
+///
+/// Tags are always serialized as an array, so the string form is lost on a round trip.
+pub(crate) fn deserialize_tags<'de, D>(deserializer: D) -> Result<Vec<Tag>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(TagsVisitor)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn deserialize() {
+        let value = json!({ "name": "Princess Luna", "type": "character" });
+        let tag: Tag = serde_json::from_value(value.clone()).expect("Tag should be deserializable");
+        assert_eq!(tag.name, "Princess Luna");
+        assert_eq!(tag.kind, TagKind::Character);
+        assert_eq!(serde_json::to_value(tag).unwrap(), value);
+
+        let value = json!({ "name": "Equestria", "type": "universe" });
+        let tag: Tag = serde_json::from_value(value).expect("Tag should be deserializable");
+        assert_eq!(tag.kind, TagKind::Other);
+        assert_eq!(
+            serde_json::to_value(tag).unwrap(),
+            json!({ "name": "Equestria", "type": "other" })
+        );
+    }
+
+    #[derive(Deserialize)]
+    struct Tags {
+        #[serde(deserialize_with = "deserialize_tags")]
+        tags: Vec<Tag>,
+    }
+
+    #[test]
+    fn deserialize_tags_array() {
+        let value = json!({ "tags": [{ "name": "Comedy", "type": "genre" }] });
+        let tags: Tags = serde_json::from_value(value).expect("tags should be deserializable");
+        assert_eq!(
+            tags.tags,
+            [Tag {
+                name: "Comedy".to_string(),
+                kind: TagKind::Genre
+            }]
+        );
+    }
+
+    #[test]
+    fn deserialize_tags_string() {
+        let value = json!({ "tags": "Comedy, Slice of Life,,Princess Luna " });
+        let tags: Tags = serde_json::from_value(value).expect("tags should be deserializable");
+        let names: Vec<_> = tags.tags.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(names, ["Comedy", "Slice of Life", "Princess Luna"]);
+        assert!(tags.tags.iter().all(|tag| tag.kind == TagKind::Other));
+
+        let tags: Tags = serde_json::from_value(json!({ "tags": "" })).unwrap();
+        assert!(tags.tags.is_empty());
+    }
+
+    #[test]
+    fn serialize_tags_string() {
+        // The string form isn't kept, the tags come back as an array of `other` tags.
+        let value = json!({ "tags": "Comedy, Princess Luna" });
+        let tags: Tags = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            serde_json::to_value(tags.tags).unwrap(),
+            json!([
+                { "name": "Comedy", "type": "other" },
+                { "name": "Princess Luna", "type": "other" },
+            ])
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use thiserror::Error;
+
+use crate::Story;
+
+/// A consistency problem found by [`Story::validate()`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+pub enum ValidationError {
+    /// [`words`](Story::words) isn't the sum of the words of the chapters.
+    #[error("`words` is {words} but the chapters add up to {chapter_words}")]
+    WordsMismatch {
+        /// The [`words`](Story::words) of the story.
+        words: u64,
+        /// The sum of the words of the chapters.
+        chapter_words: u64,
+    },
+
+    /// [`chapter_count`](Story::chapter_count) isn't the amount of chapters.
+    #[error("`chapter_count` is {chapter_count} but there are {chapters} chapters")]
+    ChapterCountMismatch {
+        /// The [`chapter_count`](Story::chapter_count) of the story.
+        chapter_count: u64,
+        /// The amount of [`chapters`](Story::chapters).
+        chapters: usize,
+    },
+
+    /// A URL field that doesn't point to Fimfiction or its image CDN.
+    #[error("`{field}` is not a Fimfiction URL: {url:?}")]
+    ForeignUrl {
+        /// Path of the field, like `url` or `chapters[0].link`.
+        field: String,
+        /// The URL.
+        url: String,
+    },
+}
+
+impl ValidationError {
+    /// Path of the field with the problem, like `words` or `chapters[0].link`.
+    pub fn field(&self) -> &str {
+        match self {
+            ValidationError::WordsMismatch { .. } => "words",
+            ValidationError::ChapterCountMismatch { .. } => "chapter_count",
+            ValidationError::ForeignUrl { field, .. } => field,
+        }
+    }
+}
+
+/// Whether `url` is an HTTP(S) URL on `fimfiction.net` or one of its subdomains.
+pub(crate) fn is_fimfiction_url(url: &str) -> bool {
+    let Some(rest) = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.split(':').next().unwrap_or_default();
+
+    host.eq_ignore_ascii_case("fimfiction.net")
+        || host.to_ascii_lowercase().ends_with(".fimfiction.net")
+}
+
+impl Story {
+    /// Checks the consistency of the story, returning every problem found.
+    ///
+    /// The totals are checked against [`chapters`](Story::chapters), like the ones set by
+    /// [`Story::recompute_totals()`], and all URLs must be on `fimfiction.net`. The content
+    /// rating isn't checked, as the numeric and text ratings are already reconciled into one when
+    /// deserializing.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+
+        let chapter_words = self.chapters.iter().map(|chapter| chapter.words).sum();
+        if self.words != chapter_words {
+            errors.push(ValidationError::WordsMismatch {
+                words: self.words,
+                chapter_words,
+            });
+        }
+
+        if self.chapter_count != self.chapters.len() as u64 {
+            errors.push(ValidationError::ChapterCountMismatch {
+                chapter_count: self.chapter_count,
+                chapters: self.chapters.len(),
+            });
+        }
+
+        let urls = [
+            ("url", Some(&self.url)),
+            ("image", self.image.as_ref()),
+            ("full_image", self.full_image.as_ref()),
+        ];
+        let urls = urls
+            .into_iter()
+            .filter_map(|(field, url)| Some((field.to_string(), url?)))
+            .chain(
+                self.groups
+                    .iter()
+                    .enumerate()
+                    .map(|(index, group)| (format!("groups[{index}].url"), &group.url)),
+            )
+            .chain(
+                self.chapters
+                    .iter()
+                    .enumerate()
+                    .map(|(index, chapter)| (format!("chapters[{index}].link"), &chapter.link)),
+            );
+        for (field, url) in urls {
+            if !is_fimfiction_url(url) {
+                errors.push(ValidationError::ForeignUrl {
+                    field,
+                    url: url.clone(),
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fimfiction_urls() {
+        assert!(is_fimfiction_url("https://www.fimfiction.net/story/428991"));
+        assert!(is_fimfiction_url(
+            "https://cdn-img.fimfiction.net/story/iwqb"
+        ));
+        assert!(is_fimfiction_url("http://fimfiction.net:80"));
+        assert!(is_fimfiction_url("https://WWW.FIMFICTION.NET/"));
+
+        assert!(!is_fimfiction_url(""));
+        assert!(!is_fimfiction_url("www.fimfiction.net/story/428991"));
+        assert!(!is_fimfiction_url("https://notfimfiction.net/story"));
+        assert!(!is_fimfiction_url("https://fimfiction.net.example.com/"));
+        assert!(!is_fimfiction_url("https://example.com/?fimfiction.net"));
+    }
+}
@@ -0,0 +1,60 @@
+use crate::Story;
+
+/// Comparison of the views of a story against the views of its chapters, given by
+/// [`Story::views_reconciliation()`].
+///
+/// Fimfiction reports as the [`views`](Story::views) of a story the views of its most viewed
+/// chapter and as its [`total_views`](Story::total_views) the sum of the views of all chapters,
+/// so any difference means that one of them is stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ViewsReport {
+    /// [`views`](Story::views) of the story.
+    pub story_views: u32,
+    /// Most views of a single chapter, `0` if there are no chapters.
+    pub chapter_max_views: u32,
+    /// [`total_views`](Story::total_views) of the story.
+    pub story_total_views: u64,
+    /// Sum of the views of all chapters.
+    pub chapter_total_views: u64,
+}
+
+impl ViewsReport {
+    /// How many more [`views`](Story::views) the story reports than its most viewed chapter.
+    pub fn views_delta(&self) -> i64 {
+        i64::from(self.story_views) - i64::from(self.chapter_max_views)
+    }
+
+    /// How many more [`total_views`](Story::total_views) the story reports than the sum of its
+    /// chapters.
+    pub fn total_views_delta(&self) -> i64 {
+        // Both are sums of `u32`s over far less than `u32::MAX` chapters.
+        self.story_total_views as i64 - self.chapter_total_views as i64
+    }
+
+    /// Whether both deltas are `0`.
+    pub fn is_consistent(&self) -> bool {
+        self.views_delta() == 0 && self.total_views_delta() == 0
+    }
+}
+
+impl Story {
+    /// Compares the views reported for the story against the views of its
+    /// [`chapters`](Story::chapters).
+    pub fn views_reconciliation(&self) -> ViewsReport {
+        ViewsReport {
+            story_views: self.views,
+            chapter_max_views: self
+                .chapters
+                .iter()
+                .map(|chapter| chapter.views)
+                .max()
+                .unwrap_or(0),
+            story_total_views: self.total_views.into(),
+            chapter_total_views: self
+                .chapters
+                .iter()
+                .map(|chapter| u64::from(chapter.views))
+                .sum(),
+        }
+    }
+}
@@ -25,11 +25,14 @@ impl<'de> Visitor<'de> for VoteVisitor {
             .map_err(|_| E::invalid_value(Unexpected::Unsigned(value), &self))
     }
 
-    fn visit_i64<E>(self, _value: i64) -> Result<Self::Value, E>
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(None)
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Ok(None),
+        }
     }
 }
 
@@ -50,6 +53,26 @@ where
     }
 }
 
+/// (De)serialization of a count that the API can hide with a negative value like a disabled vote,
+/// but where a hidden count is deserialized as `0`.
+pub mod or_zero {
+    use serde::{Deserializer, Serializer};
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u32, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        super::deserialize(deserializer).map(Option::unwrap_or_default)
+    }
+
+    pub fn serialize<S>(count: &u32, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u32(*count)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::{deserialize as deserialize_vote, serialize as serialize_vote};
@@ -85,4 +108,20 @@ mod test {
         let serialized_value = serde_json::to_value(votes).unwrap();
         assert_eq!(serialized_value, value)
     }
+
+    #[derive(Deserialize, Serialize)]
+    struct Views {
+        #[serde(with = "super::or_zero")]
+        value: u32,
+    }
+
+    #[test]
+    fn serde_hidden_count() {
+        let views: Views = serde_json::from_value(json!({ "value": 10712 })).unwrap();
+        assert_eq!(views.value, 10712);
+
+        let views: Views = serde_json::from_value(json!({ "value": -1 })).unwrap();
+        assert_eq!(views.value, 0);
+        assert_eq!(serde_json::to_value(views).unwrap(), json!({ "value": 0 }));
+    }
 }